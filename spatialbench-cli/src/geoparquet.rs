@@ -0,0 +1,206 @@
+//! Helpers for emitting [GeoParquet](https://geoparquet.org) compliant
+//! output: the file-level `"geo"` key-value metadata blob, and a per-row
+//! `bbox` covering column computed from a WKB geometry column so spatial
+//! engines can prune row groups using ordinary Parquet column statistics.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow_array::{ArrayRef, BinaryArray, Float64Array, RecordBatch, StructArray};
+use arrow_schema::{DataType, Field, Fields, Schema};
+use geo::BoundingRect;
+use serde_json::json;
+
+use crate::Table;
+
+/// Default coordinate reference system for GeoParquet output.
+pub const DEFAULT_CRS: &str = "OGC:CRS84";
+
+/// WGS84 world extent, used as the file-level `"geo"` bbox when a real
+/// dataset-level bound hasn't been computed. The per-row `bbox` column (not
+/// this value) is what readers actually prune on, so a loose dataset-level
+/// bound here is informational rather than a pruning aid.
+pub const WORLD_BBOX: [f64; 4] = [-180.0, -90.0, 180.0, 90.0];
+
+/// A table's primary geometry column and the WKB geometry type(s) it holds,
+/// for tables that support `--format=geoparquet`.
+#[derive(Debug, Clone, Copy)]
+pub struct TableGeometry {
+    pub column: &'static str,
+    pub geometry_types: &'static [&'static str],
+}
+
+/// Look up the primary geometry column for `table`, or `None` if `table`
+/// has none handled here. The zone table also returns `None`: its
+/// `--format=geoparquet` output is written by `crate::zone`.
+pub fn table_geometry(table: Table) -> Option<TableGeometry> {
+    match table {
+        Table::Trip => Some(TableGeometry {
+            column: "t_pickuploc",
+            geometry_types: &["Point"],
+        }),
+        Table::Building => Some(TableGeometry {
+            column: "b_boundary",
+            geometry_types: &["Polygon", "MultiPolygon"],
+        }),
+        Table::Customer => Some(TableGeometry {
+            column: "c_loc",
+            geometry_types: &["Point"],
+        }),
+        Table::Vehicle | Table::Driver | Table::Zone => None,
+    }
+}
+
+/// Name of the per-row bbox covering column, matching the GeoParquet
+/// `"covering"` convention (`bbox.xmin`, `bbox.ymin`, ...).
+pub const BBOX_COLUMN_NAME: &str = "bbox";
+
+/// Builds the JSON value written under the `"geo"` key in a GeoParquet
+/// file's key-value metadata.
+pub fn geo_metadata_json(
+    primary_column: &str,
+    geometry_types: &[&str],
+    dataset_bbox: [f64; 4],
+) -> String {
+    json!({
+        "version": "1.1.0",
+        "primary_column": primary_column,
+        "columns": {
+            primary_column: {
+                "encoding": "WKB",
+                "geometry_types": geometry_types,
+                "crs": DEFAULT_CRS,
+                "covering": {
+                    "bbox": {
+                        "xmin": [BBOX_COLUMN_NAME, "xmin"],
+                        "ymin": [BBOX_COLUMN_NAME, "ymin"],
+                        "xmax": [BBOX_COLUMN_NAME, "xmax"],
+                        "ymax": [BBOX_COLUMN_NAME, "ymax"],
+                    }
+                },
+            }
+        },
+        "bbox": dataset_bbox,
+    })
+    .to_string()
+}
+
+fn bbox_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("xmin", DataType::Float64, true),
+        Field::new("ymin", DataType::Float64, true),
+        Field::new("xmax", DataType::Float64, true),
+        Field::new("ymax", DataType::Float64, true),
+    ])
+}
+
+/// Compute a `bbox{xmin,ymin,xmax,ymax}` struct column from a WKB geometry
+/// column, one envelope per row (null where the geometry is null or empty).
+pub fn compute_row_bboxes(geometry: &BinaryArray) -> Result<StructArray> {
+    let mut xmin = Vec::with_capacity(geometry.len());
+    let mut ymin = Vec::with_capacity(geometry.len());
+    let mut xmax = Vec::with_capacity(geometry.len());
+    let mut ymax = Vec::with_capacity(geometry.len());
+
+    for i in 0..geometry.len() {
+        if geometry.is_null(i) {
+            xmin.push(None);
+            ymin.push(None);
+            xmax.push(None);
+            ymax.push(None);
+            continue;
+        }
+
+        let mut cursor = std::io::Cursor::new(geometry.value(i));
+        let geom: geo_types::Geometry<f64> = wkb::reader::read_wkb(&mut cursor)
+            .with_context(|| format!("failed to parse WKB geometry at row {i}"))?;
+
+        match geom.bounding_rect() {
+            Some(rect) => {
+                xmin.push(Some(rect.min().x));
+                ymin.push(Some(rect.min().y));
+                xmax.push(Some(rect.max().x));
+                ymax.push(Some(rect.max().y));
+            }
+            None => {
+                xmin.push(None);
+                ymin.push(None);
+                xmax.push(None);
+                ymax.push(None);
+            }
+        }
+    }
+
+    Ok(StructArray::new(
+        bbox_fields(),
+        vec![
+            Arc::new(Float64Array::from(xmin)) as ArrayRef,
+            Arc::new(Float64Array::from(ymin)) as ArrayRef,
+            Arc::new(Float64Array::from(xmax)) as ArrayRef,
+            Arc::new(Float64Array::from(ymax)) as ArrayRef,
+        ],
+        None,
+    ))
+}
+
+/// Append a `bbox` struct column (computed from `geometry_column`) to
+/// `batch`, returning a new batch with the extra trailing column.
+pub fn append_bbox_column(batch: &RecordBatch, geometry_column: &str) -> Result<RecordBatch> {
+    let geometry = batch
+        .column_by_name(geometry_column)
+        .with_context(|| format!("batch has no column named '{geometry_column}'"))?
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .with_context(|| format!("column '{geometry_column}' is not binary-encoded WKB"))?;
+
+    let bbox = compute_row_bboxes(geometry)?;
+
+    let mut fields: Vec<Arc<Field>> = batch.schema().fields().iter().cloned().collect();
+    fields.push(Arc::new(Field::new(
+        BBOX_COLUMN_NAME,
+        DataType::Struct(bbox_fields()),
+        true,
+    )));
+    let schema = Arc::new(Schema::new(fields));
+
+    let mut columns = batch.columns().to_vec();
+    columns.push(Arc::new(bbox));
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+/// Append four flat `{prefix}xmin`/`{prefix}ymin`/`{prefix}xmax`/`{prefix}ymax`
+/// envelope columns (computed from `geometry_column`) to `batch`.
+///
+/// Unlike [`append_bbox_column`]'s nested `bbox` struct (the GeoParquet
+/// `"covering"` convention), these are plain top-level columns so that
+/// ordinary Parquet row-group min/max statistics on them are effective even
+/// for readers with no GeoParquet support.
+pub fn append_envelope_columns(
+    batch: &RecordBatch,
+    geometry_column: &str,
+    prefix: &str,
+) -> Result<RecordBatch> {
+    let geometry = batch
+        .column_by_name(geometry_column)
+        .with_context(|| format!("batch has no column named '{geometry_column}'"))?
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .with_context(|| format!("column '{geometry_column}' is not binary-encoded WKB"))?;
+
+    let bbox = compute_row_bboxes(geometry)?;
+
+    let mut fields: Vec<Arc<Field>> = batch.schema().fields().iter().cloned().collect();
+    let mut columns = batch.columns().to_vec();
+    for (name, idx) in [("xmin", 0), ("ymin", 1), ("xmax", 2), ("ymax", 3)] {
+        fields.push(Arc::new(Field::new(
+            format!("{prefix}{name}"),
+            DataType::Float64,
+            true,
+        )));
+        columns.push(bbox.column(idx).clone());
+    }
+    let schema = Arc::new(Schema::new(fields));
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
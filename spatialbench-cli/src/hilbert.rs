@@ -0,0 +1,60 @@
+//! Hilbert space-filling curve index, used to order rows by spatial
+//! locality so that contiguous row ranges (and therefore part files) cover
+//! compact regions of the map instead of being scattered across the whole
+//! world. This makes per-file bbox statistics tight enough for readers to
+//! actually prune files.
+
+use anyhow::{Context, Result};
+use geo::Centroid;
+
+/// Bits per axis of the Hilbert grid. 2^16 cells per axis is far finer
+/// than needed to cluster zone polygons into part-sized regions.
+const ORDER: u32 = 16;
+
+/// Map a `(x, y)` grid coordinate (each in `0..2^ORDER`) to its distance
+/// along the Hilbert curve.
+pub fn hilbert_distance(mut x: u32, mut y: u32) -> u64 {
+    let mut d: u64 = 0;
+    let mut s = 1u32 << (ORDER - 1);
+    while s > 0 {
+        let rx = u64::from((x & s) > 0);
+        let ry = u64::from((y & s) > 0);
+        d += (s as u64) * (s as u64) * ((3 * rx) ^ ry);
+
+        // Rotate the quadrant.
+        if ry == 0 {
+            if rx == 1 {
+                // Only the bits below `s` are read from here on, so the
+                // wrap-around in the high bits is harmless.
+                x = (s - 1).wrapping_sub(x);
+                y = (s - 1).wrapping_sub(y);
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+
+        s >>= 1;
+    }
+    d
+}
+
+/// Normalize a `(lon, lat)` pair in WGS84 degrees onto the `2^ORDER` grid
+/// and return its Hilbert distance.
+pub fn hilbert_distance_for_lonlat(lon: f64, lat: f64) -> u64 {
+    let grid_max = ((1u64 << ORDER) - 1) as f64;
+    let nx = (((lon + 180.0) / 360.0).clamp(0.0, 1.0) * grid_max) as u32;
+    let ny = (((lat + 90.0) / 180.0).clamp(0.0, 1.0) * grid_max) as u32;
+    hilbert_distance(nx, ny)
+}
+
+/// Hilbert distance of a WKB geometry's centroid, or `u64::MAX` (sorts
+/// last) if the geometry is empty and has no centroid.
+pub fn hilbert_distance_for_wkb(wkb: &[u8]) -> Result<u64> {
+    let mut cursor = std::io::Cursor::new(wkb);
+    let geom: geo_types::Geometry<f64> = wkb::reader::read_wkb(&mut cursor)
+        .context("failed to parse WKB geometry for Hilbert sort")?;
+
+    Ok(match geom.centroid() {
+        Some(c) => hilbert_distance_for_lonlat(c.x(), c.y()),
+        None => u64::MAX,
+    })
+}
@@ -0,0 +1,52 @@
+//! Arrow IPC (`.arrow`) output support, parallel to the Parquet source path:
+//! each [`RecordBatchIterator`] source yields [`RecordBatch`]es that are
+//! written straight through to a single IPC file with no re-encoding, so
+//! downstream tools can mmap the result with zero deserialization.
+
+use std::io::{self, Write};
+
+use arrow_ipc::writer::FileWriter;
+use spatialbench_arrow::RecordBatchIterator;
+
+/// Write every batch from every source in `sources` to `writer` as one
+/// Arrow IPC file.
+///
+/// Unlike [`generate_parquet`](crate::parquet::generate_parquet), this does
+/// not fan generation out across worker threads internally. The whole
+/// write runs on a single `spawn_blocking` task so that it still yields to
+/// the async runtime at its boundaries: without this, the loop below has no
+/// `.await` point, so under `--generate-all-parts` one part's IPC write
+/// would run to completion before `buffer_unordered` ever polled the next.
+pub async fn generate_ipc<W, I>(writer: W, mut sources: I) -> Result<(), io::Error>
+where
+    W: Write + Send + 'static,
+    I: Iterator<Item: RecordBatchIterator> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || {
+        let Some(mut first) = sources.next() else {
+            return Ok(());
+        };
+
+        let mut ipc_writer = FileWriter::try_new(writer, first.schema().as_ref())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+        for batch in first.by_ref() {
+            ipc_writer
+                .write(&batch)
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        }
+        for source in sources {
+            for batch in source {
+                ipc_writer
+                    .write(&batch)
+                    .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            }
+        }
+
+        ipc_writer
+            .finish()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    })
+    .await
+    .map_err(io::Error::other)?
+}
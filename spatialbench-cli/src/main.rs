@@ -13,17 +13,27 @@
 //!     -V, --version                 Prints version information
 //!     -s, --scale-factor <FACTOR>  Scale factor for the data generation (default: 1)
 //!     -T, --tables <TABLES>        Comma-separated list of tables to generate (default: all)
-//!     -f, --format <FORMAT>        Output format: parquet, tbl or csv (default: parquet)
-//!     -o, --output-dir <DIR>       Output directory (default: current directory)
+//!     -f, --format <FORMAT>        Output format: parquet, tbl, csv, arrow, or geoparquet (trip/building/customer/zone) (default: parquet)
+//!     -o, --output-dir <DIR>       Output directory or object-store URL, e.g. s3://bucket/prefix (default: current directory)
+//!         --output-url <URL>       Alias for --output-dir, for object-store targets
 //!     -p, --parts <N>              Number of parts to split generation into (default: 1)
 //!         --part <N>               Which part to generate (1-based, default: 1)
 //!     -n, --num-threads <N>        Number of threads to use (default: number of CPUs)
 //!     -c, --parquet-compression <C> Parquet compression codec, e.g., SNAPPY, ZSTD(1), UNCOMPRESSED (default: SNAPPY)
 //!         --parquet-row-group-size <N> Target size in bytes per row group in Parquet files (default: 134,217,728)
+//!         --parquet-writer-version <V> Parquet data page format version: 1.0 or 2.0 (default: 2.0)
+//!         --parquet-dictionary <BOOL>   Enable Parquet dictionary encoding (default: true)
+//!         --parquet-write-buffer-bytes <N> In-flight write buffer before a row group is spilled (default: 64MB)
+//!         --partition-by <COL>         Write Parquet output as Hive-style col=value/ partitions
 //!     -v, --verbose                Verbose output
 //!         --stdout                 Write output to stdout instead of files
 //!```
 //!
+//! # Subcommands:
+//! `rewrite <INPUT> <OUTPUT>` re-encodes an existing Parquet file with a
+//! different compression, row group size, or dictionary setting, without
+//! regenerating it from source data.
+//!
 //! # Logging:
 //! Use the `-v` flag or `RUST_LOG` environment variable to control logging output.
 //!
@@ -42,24 +52,38 @@
 //! ```
 mod csv;
 mod generate;
+mod geoparquet;
+mod hilbert;
+mod ipc;
+mod output_target;
 mod parquet;
+mod parquet_options;
+mod parquet_stream;
+mod partition;
 mod plan;
+mod rewrite;
 mod spatial_config_file;
 mod statistics;
 mod tbl;
-mod zone_df;
+mod zone;
 
 use crate::csv::*;
 use crate::generate::{generate_in_chunks, Sink, Source};
+use crate::output_target::{BufferedUpload, ObjectStoreSink, OutputTarget};
 use crate::parquet::*;
+use crate::parquet_options::{ParquetWriteOptions, ParquetWriterVersion};
+use crate::parquet_stream::generate_parquet_streaming;
 use crate::plan::{GenerationPlan, DEFAULT_PARQUET_ROW_GROUP_BYTES};
 use crate::spatial_config_file::parse_yaml;
 use crate::statistics::WriteStatistics;
 use crate::tbl::*;
+use ::parquet::arrow::ArrowWriter;
 use ::parquet::basic::Compression;
 use clap::builder::TypedValueParser;
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
+use futures::stream::{self, StreamExt};
 use log::{debug, info, LevelFilter};
+use object_store::buffered::BufWriter as ObjectStoreBufWriter;
 use spatialbench::distribution::Distributions;
 use spatialbench::generators::{
     BuildingGenerator, CustomerGenerator, DriverGenerator, TripGenerator, VehicleGenerator,
@@ -74,6 +98,7 @@ use std::fs::{self, File};
 use std::io::{self, BufWriter, Stdout, Write};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Instant;
 
 #[derive(Parser)]
@@ -81,12 +106,23 @@ use std::time::Instant;
 #[command(version)]
 #[command(about = "TPC-H Data Generator", long_about = None)]
 struct Cli {
+    /// Re-encode an existing Parquet file instead of generating data.
+    ///
+    /// When present, none of the generation flags below apply.
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Scale factor to create
     #[arg(short, long, default_value_t = 1.)]
     scale_factor: f64,
 
-    /// Output directory for generated files (default: current directory)
-    #[arg(short, long, default_value = ".")]
+    /// Output directory for generated files (default: current directory).
+    ///
+    /// Accepts a plain local path, or a `s3://bucket/prefix`,
+    /// `gs://bucket/prefix`, `az://container/prefix`, or `file:///path` URL
+    /// to write directly to an object store instead of the local disk.
+    /// `--output-url` is accepted as an alias for object-store targets.
+    #[arg(short, long, alias = "output-url", default_value = ".")]
     output_dir: PathBuf,
 
     /// Which tables to generate (default: all)
@@ -107,7 +143,12 @@ struct Cli {
     #[arg(long)]
     part: Option<i32>,
 
-    /// Output format: tbl, csv, parquet
+    /// Output format: tbl, csv, parquet, arrow, geoparquet
+    ///
+    /// `geoparquet` is supported for the tables with a primary geometry
+    /// column (trip, building, customer, zone); it adds the GeoParquet
+    /// `"geo"` file metadata and a per-row `bbox` covering column so spatial
+    /// engines can prune row groups by bounding box.
     #[arg(short, long, default_value = "parquet")]
     format: OutputFormat,
 
@@ -153,10 +194,168 @@ struct Cli {
     /// Typical values range from 10MB to 100MB.
     #[arg(long, default_value_t = DEFAULT_PARQUET_ROW_GROUP_BYTES)]
     parquet_row_group_bytes: i64,
+
+    /// Target data page size, in bytes, within a Parquet column chunk
+    /// (default: the `parquet` crate's built-in 1MB target).
+    #[arg(long)]
+    parquet_data_page_size: Option<usize>,
+
+    /// Number of rows buffered internally by the Parquet encoder before a
+    /// column chunk is updated (default: the `parquet` crate's built-in
+    /// 1024-row batch size).
+    #[arg(long)]
+    parquet_write_batch_size: Option<usize>,
+
+    /// Parquet data page format version.
+    #[arg(long, default_value = "2.0")]
+    parquet_writer_version: ParquetWriterVersion,
+
+    /// Hard cap on rows per row group, overriding the estimate derived from
+    /// --parquet-row-group-bytes.
+    #[arg(long)]
+    parquet_max_row_group_rows: Option<usize>,
+
+    /// Enable Parquet dictionary encoding.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    parquet_dictionary: bool,
+
+    /// Target dictionary page size, in bytes, when dictionary encoding is
+    /// enabled (default: the `parquet` crate's built-in 1MB target).
+    #[arg(long)]
+    parquet_dictionary_page_size: Option<usize>,
+
+    /// Comma-separated column names (e.g. `driver_id,customer_id`) to enable
+    /// a Parquet bloom filter on, letting engines skip row groups on
+    /// equality point-lookups against these synthetic keys.
+    #[arg(long, value_delimiter = ',')]
+    parquet_bloom_filter: Vec<String>,
+
+    /// Target false-positive probability for --parquet-bloom-filter columns.
+    #[arg(long, default_value_t = 0.01)]
+    parquet_bloom_fpp: f64,
+
+    /// In-flight write buffer, in bytes, held by the streaming Parquet
+    /// writer before an encoded row group is spilled to the output.
+    ///
+    /// Bounds peak memory use for large scale factors regardless of how
+    /// much data a single invocation produces, and lets the object-store
+    /// output path upload incrementally instead of buffering a whole file.
+    #[arg(long, alias = "write-buffer-bytes", default_value_t = zone::main::DEFAULT_WRITE_BUFFER_SIZE)]
+    parquet_write_buffer_bytes: usize,
+
+    /// In-flight write buffer, in bytes, held by the streaming zone writer
+    /// before a row group is spilled to the output (default: 64MB).
+    ///
+    /// Bounds peak memory use for large scale factors regardless of how much
+    /// data the zone query produces.
+    #[arg(long, default_value_t = zone::main::DEFAULT_WRITE_BUFFER_SIZE)]
+    write_buffer_size: usize,
+
+    /// Zone-only: sort rows by the Hilbert distance of their geometry
+    /// centroid before splitting into parts, so each part file covers a
+    /// compact region of the map and its bbox stats let readers prune
+    /// whole files. Requires generating all parts in one invocation
+    /// (incompatible with --part).
+    #[arg(long, default_value_t = false)]
+    spatial_partition: bool,
+
+    /// Zone-only: sort rows by spatial locality before writing and add
+    /// `z_xmin`/`z_ymin`/`z_xmax`/`z_ymax` envelope columns, so plain
+    /// Parquet row-group min/max statistics on coordinates are effective
+    /// for pruning even without GeoParquet support. Requires --part
+    /// (single-part mode) or --spatial-partition, since it needs the full
+    /// row set in memory to sort.
+    #[arg(long, default_value_t = false)]
+    spatial_sort: bool,
+
+    /// Zone-only: read the upstream Overture `division_area` dataset from
+    /// this location instead of the public Overture S3 bucket, e.g.
+    /// `s3://my-mirror/overture`, `gs://bucket/prefix`, `az://container/prefix`,
+    /// `file:///path/to/offline/copy`, or a plain local path. Authenticated
+    /// backends pick up credentials from the environment. Omit to use
+    /// anonymous access to the public bucket (the default).
+    #[arg(long)]
+    zone_source_url: Option<String>,
+
+    /// Zone-only: custom S3 endpoint to use with --zone-source-url for
+    /// S3-compatible mirrors (e.g. MinIO). Ignored for non-S3 sources.
+    #[arg(long)]
+    zone_source_s3_endpoint: Option<String>,
+
+    /// Write each table's Parquet output as Hive-style
+    /// `column=value/part-0.parquet` partition directories instead of a
+    /// single `table.parquet` file. Only supported with --format=parquet,
+    /// and not with --stdout. The zone table does not use this flag (see
+    /// --zone-partition-by).
+    #[arg(long)]
+    partition_by: Option<String>,
+
+    /// Zone-only: write a Hive-style partitioned dataset directory, one
+    /// flat file per distinct combination of these columns' values (e.g.
+    /// `z_country=.../z_subtype=.../part-0.parquet`), instead of a single
+    /// `zone.parquet`. Requires --part (single-part mode) and is
+    /// incompatible with --spatial-partition.
+    #[arg(long, value_delimiter = ',')]
+    zone_partition_by: Vec<String>,
+
+    /// Generate every part of each selected table (other than zone, which
+    /// already generates all its parts in one invocation when --part is
+    /// omitted) in this single process instead of requiring one process per
+    /// `--part`, writing `table.N.<ext>` per part. Requires --parts and is
+    /// incompatible with --part.
+    #[arg(long, default_value_t = false)]
+    generate_all_parts: bool,
+
+    /// Maximum number of parts to generate concurrently under
+    /// --generate-all-parts (default: --num-threads).
+    #[arg(long)]
+    max_concurrency: Option<usize>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Re-encode an existing Parquet file with a different compression, row
+    /// group size, or dictionary setting, without regenerating it.
+    Rewrite(RewriteCliArgs),
+}
+
+#[derive(clap::Args)]
+struct RewriteCliArgs {
+    /// Path or URL of the existing Parquet file to re-encode.
+    input: String,
+
+    /// Path or URL to write the re-encoded Parquet file to.
+    output: String,
+
+    /// Parquet block compression format for the rewritten file.
+    ///
+    /// Supported values: UNCOMPRESSED, ZSTD(N), SNAPPY, GZIP, LZO, BROTLI, LZ4
+    #[arg(short = 'c', long, default_value = "SNAPPY")]
+    parquet_compression: Compression,
+
+    /// Target size in row group bytes for the rewritten file.
+    #[arg(long, default_value_t = DEFAULT_PARQUET_ROW_GROUP_BYTES)]
+    parquet_row_group_bytes: i64,
+
+    /// Enable dictionary encoding in the rewritten file.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    dictionary_enabled: bool,
+}
+
+impl From<RewriteCliArgs> for rewrite::RewriteArgs {
+    fn from(args: RewriteCliArgs) -> Self {
+        Self {
+            input_url: args.input,
+            output_url: args.output,
+            parquet_compression: args.parquet_compression,
+            parquet_row_group_bytes: args.parquet_row_group_bytes,
+            dictionary_enabled: args.dictionary_enabled,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-enum Table {
+pub(crate) enum Table {
     Vehicle,
     Driver,
     Customer,
@@ -244,17 +443,27 @@ impl Table {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
-enum OutputFormat {
+pub(crate) enum OutputFormat {
     Tbl,
     Csv,
     Parquet,
+    /// Parquet with GeoParquet `"geo"` metadata and a per-row bbox column.
+    GeoParquet,
+    /// Arrow IPC file format (`.arrow`). Batches are written through with no
+    /// re-encoding, so readers can mmap the result with zero deserialization.
+    Arrow,
 }
 
 #[tokio::main]
 async fn main() -> io::Result<()> {
     // Parse command line arguments
     let cli = Cli::parse();
-    cli.main().await
+    match cli.command {
+        Some(Command::Rewrite(args)) => rewrite::rewrite(args.into())
+            .await
+            .map_err(io::Error::other),
+        None => cli.main().await,
+    }
 }
 
 /// macro to create a Cli function for generating a table
@@ -269,6 +478,57 @@ async fn main() -> io::Result<()> {
 macro_rules! define_generate {
     ($FUN_NAME:ident,  $TABLE:expr, $GENERATOR:ident, $TBL_SOURCE:ty, $CSV_SOURCE:ty, $PARQUET_SOURCE:ty) => {
         async fn $FUN_NAME(&self) -> io::Result<()> {
+            if self.generate_all_parts {
+                // --parts/--part is already validated above: --generate-all-parts
+                // requires --parts and forbids --part.
+                let parts = self.parts.unwrap();
+                let concurrency = self.max_concurrency.unwrap_or(self.num_threads).max(1);
+                let scale_factor = self.scale_factor;
+                info!(
+                    "Generating all {} part(s) for table {} (max concurrency {})",
+                    parts, $TABLE, concurrency
+                );
+
+                let results: Vec<io::Result<()>> = stream::iter(1..=parts)
+                    .map(|part| async move {
+                        let filename = self.output_filename_for_part($TABLE, part);
+                        let gens = std::iter::once($GENERATOR::new(scale_factor, part, parts));
+                        match self.format {
+                            OutputFormat::Tbl => self.go(&filename, gens.map(<$TBL_SOURCE>::new)).await,
+                            OutputFormat::Csv => self.go(&filename, gens.map(<$CSV_SOURCE>::new)).await,
+                            OutputFormat::Parquet => {
+                                self.go_parquet(&filename, gens.map(<$PARQUET_SOURCE>::new))
+                                    .await
+                            }
+                            OutputFormat::GeoParquet => match geoparquet::table_geometry($TABLE) {
+                                Some(geometry) => {
+                                    self.go_geoparquet(
+                                        &filename,
+                                        geometry,
+                                        gens.map(<$PARQUET_SOURCE>::new),
+                                    )
+                                    .await
+                                }
+                                None => Err(io::Error::new(
+                                    io::ErrorKind::InvalidInput,
+                                    format!("{} table does not support --format=geoparquet", $TABLE),
+                                )),
+                            },
+                            OutputFormat::Arrow => {
+                                self.go_ipc(&filename, gens.map(<$PARQUET_SOURCE>::new)).await
+                            }
+                        }
+                    })
+                    .buffer_unordered(concurrency)
+                    .collect()
+                    .await;
+
+                return results
+                    .into_iter()
+                    .collect::<io::Result<Vec<()>>>()
+                    .map(|_| ());
+            }
+
             let filename = self.output_filename($TABLE);
             let plan = GenerationPlan::try_new(
                 &$TABLE,
@@ -292,6 +552,19 @@ macro_rules! define_generate {
                     self.go_parquet(&filename, gens.map(<$PARQUET_SOURCE>::new))
                         .await
                 }
+                OutputFormat::GeoParquet => match geoparquet::table_geometry($TABLE) {
+                    Some(geometry) => {
+                        self.go_geoparquet(&filename, geometry, gens.map(<$PARQUET_SOURCE>::new))
+                            .await
+                    }
+                    None => Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("{} table does not support --format=geoparquet", $TABLE),
+                    )),
+                },
+                OutputFormat::Arrow => {
+                    self.go_ipc(&filename, gens.map(<$PARQUET_SOURCE>::new)).await
+                }
             }
         }
     };
@@ -309,8 +582,9 @@ impl Cli {
             debug!("Logging configured from environment variables");
         }
 
-        // Create output directory if it doesn't exist and we are not writing to stdout.
-        if !self.stdout {
+        // Create output directory if it doesn't exist and we are not writing to stdout
+        // or to a remote object store (which has no directories to create).
+        if !self.stdout && !self.is_remote_output() {
             fs::create_dir_all(&self.output_dir)?;
         }
 
@@ -377,8 +651,20 @@ impl Cli {
         let elapsed = start.elapsed();
         info!("Created static distributions and text pools in {elapsed:?}");
 
+        // --partition-by changes the on-disk layout (one file per distinct
+        // partition value) rather than just tuning the parquet writer, so
+        // unlike the options below it is a hard error outside plain parquet
+        // output rather than a warning. The geoparquet writer has no
+        // partitioned layout, so it is rejected too.
+        if self.partition_by.is_some() && self.format != OutputFormat::Parquet {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--partition-by is only supported with --format=parquet",
+            ));
+        }
+
         // Warn if parquet specific options are set but not generating parquet
-        if self.format != OutputFormat::Parquet {
+        if !matches!(self.format, OutputFormat::Parquet | OutputFormat::GeoParquet) {
             if self.parquet_compression != Compression::SNAPPY {
                 eprintln!(
                     "Warning: Parquet compression option set but not generating Parquet files"
@@ -389,6 +675,43 @@ impl Cli {
                     "Warning: Parquet row group size option set but not generating Parquet files"
                 );
             }
+            if self.parquet_data_page_size.is_some()
+                || self.parquet_write_batch_size.is_some()
+                || self.parquet_writer_version != ParquetWriterVersion::V2
+                || self.parquet_max_row_group_rows.is_some()
+                || !self.parquet_dictionary
+                || self.parquet_dictionary_page_size.is_some()
+                || !self.parquet_bloom_filter.is_empty()
+                || self.parquet_write_buffer_bytes != zone::main::DEFAULT_WRITE_BUFFER_SIZE
+            {
+                eprintln!(
+                    "Warning: Parquet writer-tuning options set but not generating Parquet files"
+                );
+            }
+        }
+
+        if self.generate_all_parts {
+            if self.part.is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Cannot specify --part with --generate-all-parts",
+                ));
+            }
+            if self.parts.is_none() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--generate-all-parts requires --parts to be set",
+                ));
+            }
+            if self.stdout {
+                // Parts run concurrently under buffer_unordered; writing
+                // more than one of them to the same stdout handle at once
+                // would interleave their bytes.
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--generate-all-parts is not supported with --stdout",
+                ));
+            }
         }
 
         // Generate each table
@@ -408,25 +731,31 @@ impl Cli {
     }
 
     async fn generate_zone(&self) -> io::Result<()> {
-        match self.format {
-            OutputFormat::Parquet => {
-                let args = zone_df::ZoneDfArgs {
-                    scale_factor: 1.0f64.max(self.scale_factor),
-                    output_dir: self.output_dir.clone(),
-                    parts: self.parts.unwrap_or(1),
-                    part: self.part.unwrap_or(1),
-                    parquet_row_group_bytes: self.parquet_row_group_bytes,
-                    parquet_compression: self.parquet_compression,
-                };
-                zone_df::generate_zone_parquet(args)
-                    .await
-                    .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
-            }
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Zone table is only supported in --format=parquet (via DataFusion/S3).",
-            )),
-        }
+        let source_store = match &self.zone_source_url {
+            Some(url) => zone::config::SourceStore::parse(
+                url,
+                self.zone_source_s3_endpoint.clone(),
+            )
+            .map_err(io::Error::other)?,
+            None => zone::config::SourceStore::default(),
+        };
+
+        zone::main::generate_zone(
+            self.format,
+            self.scale_factor,
+            source_store,
+            self.output_dir.to_string_lossy().into_owned(),
+            self.parts,
+            self.part,
+            None,
+            self.parquet_row_group_bytes,
+            self.parquet_write_options(),
+            self.write_buffer_size,
+            self.spatial_partition,
+            self.spatial_sort,
+            self.zone_partition_by.clone(),
+        )
+        .await
     }
 
     define_generate!(
@@ -476,16 +805,58 @@ impl Cli {
             OutputFormat::Tbl => "tbl",
             OutputFormat::Csv => "csv",
             OutputFormat::Parquet => "parquet",
+            OutputFormat::GeoParquet => "parquet",
+            OutputFormat::Arrow => "arrow",
         };
         format!("{}.{extension}", table.name())
     }
 
+    /// Output filename for the given 1-based `part` when `--generate-all-parts`
+    /// produces every part of `table` in a single invocation, analogous to the
+    /// zone table's own `zone.N.parquet` naming for its all-in-one-invocation path.
+    fn output_filename_for_part(&self, table: Table, part: i32) -> String {
+        let extension = match self.format {
+            OutputFormat::Tbl => "tbl",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Parquet => "parquet",
+            OutputFormat::GeoParquet => "parquet",
+            OutputFormat::Arrow => "arrow",
+        };
+        format!("{}.{part}.{extension}", table.name())
+    }
+
     /// return a file for writing the given filename in the output directory
     fn new_output_file(&self, filename: &str) -> io::Result<File> {
         let path = self.output_dir.join(filename);
         File::create(path)
     }
 
+    /// True if `--output-dir` names a remote object store (`s3://`, `gs://`,
+    /// `az://`) rather than a local path.
+    fn is_remote_output(&self) -> bool {
+        OutputTarget::is_remote(&self.output_dir.to_string_lossy())
+    }
+
+    /// Resolve `--output-dir` into an [`OutputTarget`] for a remote output.
+    fn remote_output_target(&self) -> io::Result<OutputTarget> {
+        OutputTarget::resolve(&self.output_dir.to_string_lossy()).map_err(io::Error::other)
+    }
+
+    /// Collect the `--parquet-*` CLI flags into a [`ParquetWriteOptions`].
+    fn parquet_write_options(&self) -> ParquetWriteOptions {
+        ParquetWriteOptions {
+            compression: self.parquet_compression,
+            data_page_size_bytes: self.parquet_data_page_size,
+            write_batch_size: self.parquet_write_batch_size,
+            writer_version: self.parquet_writer_version,
+            max_row_group_rows: self.parquet_max_row_group_rows,
+            dictionary_enabled: self.parquet_dictionary,
+            dictionary_page_size_bytes: self.parquet_dictionary_page_size,
+            bloom_filter_columns: self.parquet_bloom_filter.clone(),
+            bloom_filter_fpp: self.parquet_bloom_fpp,
+        }
+    }
+
     /// Generates the output file from the sources
     async fn go<I>(&self, filename: &str, sources: I) -> Result<(), io::Error>
     where
@@ -495,26 +866,226 @@ impl Cli {
         if self.stdout {
             let sink = WriterSink::new(io::stdout());
             generate_in_chunks(sink, sources, self.num_threads).await
+        } else if self.is_remote_output() {
+            let target = self.remote_output_target()?;
+            let path = target.base_path.child(filename);
+            let sink = ObjectStoreSink::new(target, path);
+            generate_in_chunks(sink, sources, self.num_threads).await
         } else {
             let sink = WriterSink::new(self.new_output_file(filename)?);
             generate_in_chunks(sink, sources, self.num_threads).await
         }
     }
 
-    /// Generates an output parquet file from the sources
+    /// Generates an output parquet file from the sources, streaming row
+    /// groups through a bounded-memory writer (`--parquet-write-buffer-bytes`)
+    /// rather than buffering the whole file before it's written.
     async fn go_parquet<I>(&self, filename: &str, sources: I) -> Result<(), io::Error>
+    where
+        I: Iterator<Item: RecordBatchIterator> + Send + 'static,
+    {
+        if let Some(column) = self.partition_by.clone() {
+            return self.go_parquet_partitioned(filename, &column, sources).await;
+        }
+
+        let write_buffer_bytes = self.parquet_write_buffer_bytes;
+        let row_group_bytes = self.parquet_row_group_bytes;
+        if self.stdout {
+            generate_parquet_streaming(
+                tokio::io::stdout(),
+                sources,
+                self.parquet_write_options(),
+                write_buffer_bytes,
+                row_group_bytes,
+            )
+            .await
+        } else if self.is_remote_output() {
+            let target = self.remote_output_target()?;
+            let path = target.base_path.child(filename);
+            let writer = ObjectStoreBufWriter::new(Arc::clone(&target.store), path);
+            generate_parquet_streaming(
+                writer,
+                sources,
+                self.parquet_write_options(),
+                write_buffer_bytes,
+                row_group_bytes,
+            )
+            .await
+        } else {
+            let file = tokio::fs::File::create(self.output_dir.join(filename)).await?;
+            generate_parquet_streaming(
+                file,
+                sources,
+                self.parquet_write_options(),
+                write_buffer_bytes,
+                row_group_bytes,
+            )
+            .await
+        }
+    }
+
+    /// Generates a table's Parquet output as Hive-style
+    /// `column=value/part-0.parquet` partition directories instead of a
+    /// single `table.parquet` file (`--partition-by`).
+    ///
+    /// Unlike the streaming `generate_parquet_streaming` path, this collects every
+    /// batch into memory up front, since rows must be grouped by partition
+    /// value before any file can be written.
+    async fn go_parquet_partitioned<I>(
+        &self,
+        filename: &str,
+        column: &str,
+        sources: I,
+    ) -> Result<(), io::Error>
     where
         I: Iterator<Item: RecordBatchIterator> + 'static,
     {
         if self.stdout {
-            // write to stdout
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--partition-by is not supported with --stdout",
+            ));
+        }
+
+        let table_dir = filename.trim_end_matches(".parquet");
+
+        let mut schema = None;
+        let mut batches = Vec::new();
+        for mut source in sources {
+            if schema.is_none() {
+                schema = Some(source.schema().clone());
+            }
+            batches.extend(source.by_ref());
+        }
+        let Some(schema) = schema else {
+            return Ok(());
+        };
+
+        let partitions = partition::partition_batches(&batches, &schema, column)
+            .map_err(io::Error::other)?;
+        let options = self.parquet_write_options();
+
+        for (value, batch) in partitions {
+            let props = options.build_writer_properties(batch.num_rows().max(1));
+            let mut buf = Vec::new();
+            let mut writer = ArrowWriter::try_new(&mut buf, schema.clone(), Some(props))
+                .map_err(io::Error::other)?;
+            writer.write(&batch).map_err(io::Error::other)?;
+            writer.close().map_err(io::Error::other)?;
+
+            let partition_dir = format!("{column}={value}");
+            if self.is_remote_output() {
+                let target = self.remote_output_target()?;
+                let path = target
+                    .base_path
+                    .child(table_dir)
+                    .child(partition_dir.as_str())
+                    .child("part-0.parquet");
+                target
+                    .store
+                    .put(&path, buf.into())
+                    .await
+                    .map_err(io::Error::other)?;
+            } else {
+                let dir = self.output_dir.join(table_dir).join(&partition_dir);
+                fs::create_dir_all(&dir)?;
+                fs::write(dir.join("part-0.parquet"), buf)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generates a table's GeoParquet output: file-level `"geo"` metadata
+    /// plus a per-row `bbox` covering column computed from `geometry`
+    /// (`--format=geoparquet`, for tables with a known primary geometry
+    /// column; the zone table has its own copy of this in `crate::zone`).
+    ///
+    /// Like `go_parquet_partitioned`, this collects every batch into memory
+    /// up front, since the bbox column and writer properties are computed
+    /// from the whole result.
+    async fn go_geoparquet<I>(
+        &self,
+        filename: &str,
+        geometry: geoparquet::TableGeometry,
+        sources: I,
+    ) -> Result<(), io::Error>
+    where
+        I: Iterator<Item: RecordBatchIterator> + 'static,
+    {
+        let mut schema = None;
+        let mut batches = Vec::new();
+        for mut source in sources {
+            if schema.is_none() {
+                schema = Some(source.schema().clone());
+            }
+            batches.extend(source.by_ref());
+        }
+        let Some(schema) = schema else {
+            return Ok(());
+        };
+
+        let combined =
+            arrow_select::concat::concat_batches(&schema, &batches).map_err(io::Error::other)?;
+        let with_bbox = geoparquet::append_bbox_column(&combined, geometry.column)
+            .map_err(io::Error::other)?;
+
+        let geo_json = geoparquet::geo_metadata_json(
+            geometry.column,
+            geometry.geometry_types,
+            geoparquet::WORLD_BBOX,
+        );
+        let key_value_metadata = vec![::parquet::file::metadata::KeyValue::new(
+            "geo".to_string(),
+            geo_json,
+        )];
+        let props = self
+            .parquet_write_options()
+            .build_writer_properties_with_metadata(with_bbox.num_rows().max(1), key_value_metadata);
+
+        let mut buf = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buf, with_bbox.schema(), Some(props))
+            .map_err(io::Error::other)?;
+        writer.write(&with_bbox).map_err(io::Error::other)?;
+        writer.close().map_err(io::Error::other)?;
+
+        if self.stdout {
+            io::stdout().write_all(&buf)
+        } else if self.is_remote_output() {
+            let target = self.remote_output_target()?;
+            let path = target.base_path.child(filename);
+            target
+                .store
+                .put(&path, buf.into())
+                .await
+                .map_err(io::Error::other)
+        } else {
+            fs::write(self.output_dir.join(filename), buf)
+        }
+    }
+
+    /// Generates an output Arrow IPC file from the sources
+    async fn go_ipc<I>(&self, filename: &str, sources: I) -> Result<(), io::Error>
+    where
+        I: Iterator<Item: RecordBatchIterator> + Send + 'static,
+    {
+        if self.stdout {
             let writer = BufWriter::with_capacity(32 * 1024 * 1024, io::stdout()); // 32MB buffer
-            generate_parquet(writer, sources, self.num_threads, self.parquet_compression).await
+            ipc::generate_ipc(writer, sources).await
+        } else if self.is_remote_output() {
+            let target = self.remote_output_target()?;
+            let path = target.base_path.child(filename);
+            let writer = BufferedUpload::new();
+            let upload = writer.clone();
+            ipc::generate_ipc(writer, sources).await?;
+            upload
+                .upload(&target, &path)
+                .await
+                .map_err(io::Error::other)
         } else {
-            // write to a file
             let file = self.new_output_file(filename)?;
             let writer = BufWriter::with_capacity(32 * 1024 * 1024, file); // 32MB buffer
-            generate_parquet(writer, sources, self.num_threads, self.parquet_compression).await
+            ipc::generate_ipc(writer, sources).await
         }
     }
 }
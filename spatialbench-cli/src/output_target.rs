@@ -0,0 +1,218 @@
+//! Resolves a user-supplied output location into an [`ObjectStore`] plus the
+//! [`Path`](object_store::path::Path) within that store to write to.
+//!
+//! Callers pass a URL-style target such as `s3://bucket/prefix`,
+//! `gs://bucket/prefix`, `az://container/prefix`, `file:///tmp/out`, or a
+//! plain local filesystem path (treated the same as `file://`). Credentials
+//! are picked up from the environment, the same way the underlying cloud
+//! SDKs already do (e.g. `AWS_ACCESS_KEY_ID`/`AWS_REGION`,
+//! `GOOGLE_APPLICATION_CREDENTIALS`, `AZURE_STORAGE_ACCOUNT`/`AZURE_STORAGE_KEY`).
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Context, Result};
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use url::Url;
+
+use crate::generate::Sink;
+use crate::parquet::IntoSize;
+
+/// An output destination resolved from a URL-style target string.
+pub struct OutputTarget {
+    pub store: Arc<dyn ObjectStore>,
+    pub base_path: ObjectPath,
+}
+
+impl OutputTarget {
+    /// Resolve `target` into an [`ObjectStore`] and the base [`Path`] within
+    /// it that generated files should be written under.
+    pub fn resolve(target: &str) -> Result<Self> {
+        match Url::parse(target) {
+            Ok(url) if url.scheme().len() > 1 => Self::resolve_url(&url),
+            _ => Self::resolve_local(target),
+        }
+    }
+
+    /// True if `target` names a remote object store (`s3://`, `gs://`,
+    /// `az://`, ...) rather than a local filesystem path or `file://` URL.
+    pub fn is_remote(target: &str) -> bool {
+        matches!(Url::parse(target), Ok(url) if url.scheme().len() > 1 && url.scheme() != "file")
+    }
+
+    fn resolve_url(url: &Url) -> Result<Self> {
+        match url.scheme() {
+            "s3" => Self::resolve_s3(url),
+            "gs" | "gcs" => Self::resolve_gcs(url),
+            "az" | "azure" | "abfs" => Self::resolve_azure(url),
+            "file" => Self::resolve_local(url.path()),
+            other => Err(anyhow!(
+                "Unsupported output scheme '{other}://' (expected s3, gs, az, or file)"
+            )),
+        }
+    }
+
+    fn resolve_s3(url: &Url) -> Result<Self> {
+        let bucket = url
+            .host_str()
+            .ok_or_else(|| anyhow!("S3 output target '{url}' is missing a bucket name"))?;
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .with_context(|| format!("failed to build S3 client for bucket '{bucket}'"))?;
+        Ok(Self {
+            store: Arc::new(store),
+            base_path: ObjectPath::from(url.path().trim_start_matches('/')),
+        })
+    }
+
+    fn resolve_gcs(url: &Url) -> Result<Self> {
+        let bucket = url
+            .host_str()
+            .ok_or_else(|| anyhow!("GCS output target '{url}' is missing a bucket name"))?;
+        let store = GoogleCloudStorageBuilder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .with_context(|| format!("failed to build GCS client for bucket '{bucket}'"))?;
+        Ok(Self {
+            store: Arc::new(store),
+            base_path: ObjectPath::from(url.path().trim_start_matches('/')),
+        })
+    }
+
+    fn resolve_azure(url: &Url) -> Result<Self> {
+        let container = url
+            .host_str()
+            .ok_or_else(|| anyhow!("Azure output target '{url}' is missing a container name"))?;
+        let store = MicrosoftAzureBuilder::from_env()
+            .with_container_name(container)
+            .build()
+            .with_context(|| format!("failed to build Azure client for container '{container}'"))?;
+        Ok(Self {
+            store: Arc::new(store),
+            base_path: ObjectPath::from(url.path().trim_start_matches('/')),
+        })
+    }
+
+    /// Resolve `target` as a path to a single object (e.g. an existing
+    /// Parquet file), splitting off the final path segment as the object's
+    /// name within its parent directory/prefix. A bare file name with no
+    /// `/` is resolved within the current directory.
+    pub fn resolve_object(target: &str) -> Result<(Self, ObjectPath)> {
+        let (dir, file) = target.rsplit_once('/').unwrap_or((".", target));
+        let target_dir = Self::resolve(dir)?;
+        let object_path = target_dir.base_path.child(file);
+        Ok((target_dir, object_path))
+    }
+
+    fn resolve_local(path: &str) -> Result<Self> {
+        std::fs::create_dir_all(path)
+            .with_context(|| format!("failed to create local output directory '{path}'"))?;
+        let store = LocalFileSystem::new_with_prefix(path)
+            .with_context(|| format!("failed to open local output directory '{path}'"))?;
+        Ok(Self {
+            store: Arc::new(store),
+            base_path: ObjectPath::from(""),
+        })
+    }
+}
+
+/// [`Sink`] implementation for the raw tbl/CSV byte path that buffers
+/// written bytes in memory and uploads them as a single object on `flush`.
+///
+/// This is not an incremental multipart upload: the whole file accumulates
+/// in memory first and is sent with a single `put`, whereas the local-file
+/// sink writes each buffer through to the file as it arrives.
+pub struct ObjectStoreSink {
+    target: OutputTarget,
+    path: ObjectPath,
+    buffer: Vec<u8>,
+}
+
+impl ObjectStoreSink {
+    pub fn new(target: OutputTarget, path: ObjectPath) -> Self {
+        Self {
+            target,
+            path,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl Sink for ObjectStoreSink {
+    fn sink(&mut self, buffer: &[u8]) -> Result<(), io::Error> {
+        self.buffer.extend_from_slice(buffer);
+        Ok(())
+    }
+
+    fn flush(self) -> Result<(), io::Error> {
+        // `flush` is a synchronous `Sink` method called inline from the
+        // tokio task driving generation (not from a `spawn_blocking`
+        // worker), so a bare `Handle::current().block_on(..)` here would
+        // panic with "Cannot start a runtime from within a runtime".
+        // `block_in_place` hands this worker thread's other tasks off to
+        // the rest of the (multi-threaded) pool for the duration of the
+        // blocking call, which makes driving the upload to completion here
+        // safe.
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current()
+                .block_on(self.target.store.put(&self.path, self.buffer.into()))
+        })
+        .map(|_| ())
+        .map_err(io::Error::other)
+    }
+}
+
+/// `Write` sink that buffers everything written to it in memory, for use
+/// with whole-file encoders (Parquet, Arrow IPC) that take a single owned
+/// writer. Call [`BufferedUpload::upload`] once the encoder has finished
+/// writing to push the buffered bytes to the resolved [`OutputTarget`].
+#[derive(Clone, Default)]
+pub struct BufferedUpload(Arc<Mutex<Vec<u8>>>);
+
+impl BufferedUpload {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Upload the buffered bytes to `path` within `target`. Must be called
+    /// after every clone of this writer has been dropped.
+    pub async fn upload(self, target: &OutputTarget, path: &ObjectPath) -> Result<()> {
+        let bytes = Arc::try_unwrap(self.0)
+            .map_err(|_| anyhow!("BufferedUpload still has outstanding writer handles"))?
+            .into_inner()
+            .map_err(|_| anyhow!("BufferedUpload lock was poisoned"))?;
+        target.store.put(path, bytes.into()).await?;
+        Ok(())
+    }
+}
+
+impl Write for BufferedUpload {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut inner = self
+            .0
+            .lock()
+            .map_err(|_| io::Error::other("BufferedUpload lock was poisoned"))?;
+        inner.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl IntoSize for BufferedUpload {
+    fn into_size(self) -> Result<usize, io::Error> {
+        let inner = self
+            .0
+            .lock()
+            .map_err(|_| io::Error::other("BufferedUpload lock was poisoned"))?;
+        Ok(inner.len())
+    }
+}
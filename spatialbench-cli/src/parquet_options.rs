@@ -0,0 +1,97 @@
+//! Parquet `WriterProperties` tuning shared across every table's Parquet
+//! output path, including the `zone` table and the GeoParquet writers,
+//! which attach their `"geo"` file metadata through
+//! [`ParquetWriteOptions::build_writer_properties_with_metadata`].
+
+use clap::ValueEnum;
+use parquet::basic::Compression as ParquetCompression;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::{WriterProperties, WriterPropertiesBuilder, WriterVersion};
+use parquet::schema::types::ColumnPath;
+
+/// Parquet data page format version, exposed on the CLI as `1.0`/`2.0`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ParquetWriterVersion {
+    #[value(name = "1.0")]
+    V1,
+    #[value(name = "2.0")]
+    V2,
+}
+
+impl From<ParquetWriterVersion> for WriterVersion {
+    fn from(version: ParquetWriterVersion) -> Self {
+        match version {
+            ParquetWriterVersion::V1 => WriterVersion::PARQUET_1_0,
+            ParquetWriterVersion::V2 => WriterVersion::PARQUET_2_0,
+        }
+    }
+}
+
+/// Parquet `WriterProperties` knobs exposed on the CLI, applied uniformly to
+/// every table's Parquet and GeoParquet output. Arrow IPC output does not
+/// use them.
+#[derive(Clone)]
+pub struct ParquetWriteOptions {
+    pub compression: ParquetCompression,
+    pub data_page_size_bytes: Option<usize>,
+    pub write_batch_size: Option<usize>,
+    pub writer_version: ParquetWriterVersion,
+    pub max_row_group_rows: Option<usize>,
+    pub dictionary_enabled: bool,
+    pub dictionary_page_size_bytes: Option<usize>,
+    /// Columns (by name, e.g. `driver_id`) to enable a Parquet bloom filter
+    /// on, for engines that push down equality predicates.
+    pub bloom_filter_columns: Vec<String>,
+    /// Target false-positive probability for `bloom_filter_columns`.
+    pub bloom_filter_fpp: f64,
+}
+
+impl ParquetWriteOptions {
+    fn builder(&self, rows_per_group: usize) -> WriterPropertiesBuilder {
+        let max_row_group_size = self.max_row_group_rows.unwrap_or(rows_per_group);
+
+        let mut builder = WriterProperties::builder()
+            .set_compression(self.compression)
+            .set_max_row_group_size(max_row_group_size)
+            .set_writer_version(self.writer_version.into())
+            .set_dictionary_enabled(self.dictionary_enabled);
+
+        if let Some(size) = self.data_page_size_bytes {
+            builder = builder.set_data_page_size_limit(size);
+        }
+        if let Some(size) = self.write_batch_size {
+            builder = builder.set_write_batch_size(size);
+        }
+        if let Some(size) = self.dictionary_page_size_bytes {
+            builder = builder.set_dictionary_page_size_limit(size);
+        }
+
+        for column in &self.bloom_filter_columns {
+            let path = ColumnPath::from(column.as_str());
+            builder = builder
+                .set_column_bloom_filter_enabled(path.clone(), true)
+                .set_column_bloom_filter_fpp(path, self.bloom_filter_fpp);
+        }
+
+        builder
+    }
+
+    /// Build a `WriterProperties` targeting `rows_per_group` rows per row
+    /// group, unless `max_row_group_rows` overrides it.
+    pub fn build_writer_properties(&self, rows_per_group: usize) -> WriterProperties {
+        self.builder(rows_per_group).build()
+    }
+
+    /// Like [`Self::build_writer_properties`], but also attaches
+    /// `key_value_metadata` (e.g. GeoParquet `"geo"` file metadata) to the
+    /// result.
+    pub fn build_writer_properties_with_metadata(
+        &self,
+        rows_per_group: usize,
+        key_value_metadata: Vec<KeyValue>,
+    ) -> WriterProperties {
+        self.builder(rows_per_group)
+            .set_key_value_metadata(Some(key_value_metadata))
+            .build()
+    }
+}
@@ -0,0 +1,117 @@
+//! Bounded-memory streaming Parquet writer for the regular per-table
+//! Parquet path (`--parquet-write-buffer-bytes`), used in place of
+//! `generate_parquet`'s single fully-buffered writer.
+//!
+//! A blocking task reads batches from `sources` in turn and sends them over
+//! a channel to an [`AsyncArrowWriter`], which spills an encoded row group
+//! to the underlying writer as soon as its buffered size crosses
+//! `write_buffer_bytes`. Peak memory therefore stays bounded regardless of
+//! scale factor, and the underlying writer (e.g. an
+//! [`object_store::buffered::BufWriter`]) can upload what's been flushed so
+//! far instead of only seeing bytes once the whole file is done.
+
+use std::io;
+
+use parquet::arrow::async_writer::AsyncArrowWriter;
+use spatialbench_arrow::RecordBatchIterator;
+use tokio::io::AsyncWrite;
+use tokio::sync::mpsc;
+
+use crate::parquet_options::ParquetWriteOptions;
+use crate::plan::DEFAULT_PARQUET_ROW_GROUP_BYTES;
+
+/// Row group size target used when the first batch is empty and a sample
+/// can't be taken, matching the `parquet` crate's own built-in default.
+const DEFAULT_ROWS_PER_GROUP: usize = 1_048_576;
+
+/// Depth of the channel between the batch-reading task and the writer task.
+/// Small on purpose: backpressure here is what keeps unbounded numbers of
+/// batches from piling up ahead of `write_buffer_bytes`.
+const CHANNEL_CAPACITY: usize = 4;
+
+/// Pick a row group size (in rows) so that, given a sample batch's measured
+/// bytes-per-row, each row group targets `target_bytes`. Clamped the same
+/// way as `rewrite.rs`'s `rows_per_group_for_target`, to keep row group
+/// counts reasonable at either extreme.
+fn rows_per_group_for_target(sample_bytes: usize, sample_rows: i64, target_bytes: i64) -> usize {
+    let effective_target = if target_bytes <= 0 {
+        DEFAULT_PARQUET_ROW_GROUP_BYTES
+    } else {
+        target_bytes
+    };
+    let bytes_per_row = sample_bytes as f64 / sample_rows.max(1) as f64;
+    let est = (effective_target as f64 / bytes_per_row).floor();
+    est.clamp(1000.0, 32767.0) as usize
+}
+
+/// Write every batch from every source in `sources` to `writer` as a single
+/// Parquet file, flushing row groups once `write_buffer_bytes` of unflushed
+/// data has accumulated.
+///
+/// Row group boundaries (`--parquet-row-group-bytes`) are estimated from the
+/// first batch's measured size, since (unlike `rewrite.rs`, which rewrites
+/// an already-materialized file) the total size of a just-generated
+/// streaming source isn't known up front.
+pub async fn generate_parquet_streaming<W, I>(
+    writer: W,
+    mut sources: I,
+    options: ParquetWriteOptions,
+    write_buffer_bytes: usize,
+    target_row_group_bytes: i64,
+) -> Result<(), io::Error>
+where
+    W: AsyncWrite + Unpin + Send,
+    I: Iterator<Item: RecordBatchIterator> + Send + 'static,
+{
+    let Some(mut first) = sources.next() else {
+        return Ok(());
+    };
+    let schema = first.schema().clone();
+
+    let sample = first.next();
+    let rows_per_group = options.max_row_group_rows.unwrap_or_else(|| {
+        sample
+            .as_ref()
+            .filter(|batch| batch.num_rows() > 0)
+            .map(|batch| {
+                rows_per_group_for_target(
+                    batch.get_array_memory_size(),
+                    batch.num_rows() as i64,
+                    target_row_group_bytes,
+                )
+            })
+            .unwrap_or(DEFAULT_ROWS_PER_GROUP)
+    });
+
+    let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let feeder = tokio::task::spawn_blocking(move || {
+        if let Some(batch) = sample {
+            if tx.blocking_send(batch).is_err() {
+                return;
+            }
+        }
+        for batch in first.by_ref() {
+            if tx.blocking_send(batch).is_err() {
+                return;
+            }
+        }
+        for source in sources {
+            for batch in source {
+                if tx.blocking_send(batch).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    let props = options.build_writer_properties(rows_per_group);
+    let mut arrow_writer = AsyncArrowWriter::try_new(writer, schema, write_buffer_bytes, Some(props))
+        .map_err(io::Error::other)?;
+
+    while let Some(batch) = rx.recv().await {
+        arrow_writer.write(&batch).await.map_err(io::Error::other)?;
+    }
+
+    arrow_writer.close().await.map_err(io::Error::other)?;
+    feeder.await.map_err(io::Error::other)
+}
@@ -0,0 +1,106 @@
+//! Splits a set of record batches into Hive-style partitions (`col=value`)
+//! keyed by the value of a single column, so callers can write one file per
+//! partition directory instead of one file for the whole table.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context, Result};
+use arrow_array::{
+    Array, Int32Array, Int64Array, RecordBatch, StringArray, UInt32Array, UInt64Array,
+};
+use arrow_schema::SchemaRef;
+
+/// Render the value of `array` at `row` as a Hive partition directory
+/// segment (`col=value`). Only the column types this benchmark actually
+/// uses for partition keys (string ids, spatial grid cell ids, and integer
+/// date/zone buckets) are supported.
+fn partition_value_as_string(array: &dyn Array, row: usize) -> Result<String> {
+    if array.is_null(row) {
+        return Ok("__HIVE_DEFAULT_PARTITION__".to_string());
+    }
+
+    if let Some(a) = array.as_any().downcast_ref::<StringArray>() {
+        return Ok(a.value(row).to_string());
+    }
+    if let Some(a) = array.as_any().downcast_ref::<Int32Array>() {
+        return Ok(a.value(row).to_string());
+    }
+    if let Some(a) = array.as_any().downcast_ref::<Int64Array>() {
+        return Ok(a.value(row).to_string());
+    }
+    if let Some(a) = array.as_any().downcast_ref::<UInt32Array>() {
+        return Ok(a.value(row).to_string());
+    }
+    if let Some(a) = array.as_any().downcast_ref::<UInt64Array>() {
+        return Ok(a.value(row).to_string());
+    }
+
+    Err(anyhow!(
+        "--partition-by does not support column type {:?}",
+        array.data_type()
+    ))
+}
+
+/// Group `batches` by the value of `column`, returning one combined
+/// `RecordBatch` per distinct value, keyed by its Hive directory segment
+/// (`value`, not `column=value` -- callers add the column name).
+///
+/// Partitions are returned in ascending order of their string value, so
+/// output is deterministic across runs.
+pub fn partition_batches(
+    batches: &[RecordBatch],
+    schema: &SchemaRef,
+    column: &str,
+) -> Result<Vec<(String, RecordBatch)>> {
+    let columns = [column.to_string()];
+    partition_batches_multi(batches, schema, &columns)?
+        .into_iter()
+        .map(|(mut key, batch)| Ok((key.pop().expect("single-column key"), batch)))
+        .collect()
+}
+
+/// Group `batches` by the distinct combination of values across `columns`,
+/// returning one combined `RecordBatch` per combination, keyed by the
+/// ordered list of directory segment values (one per column in `columns`,
+/// not yet joined into `col=value` form -- callers zip against `columns`).
+///
+/// Partitions are returned in ascending order of their key, so output is
+/// deterministic across runs.
+pub fn partition_batches_multi(
+    batches: &[RecordBatch],
+    schema: &SchemaRef,
+    columns: &[String],
+) -> Result<Vec<(Vec<String>, RecordBatch)>> {
+    let combined = arrow_select::concat::concat_batches(schema, batches)?;
+
+    let partition_columns = columns
+        .iter()
+        .map(|column| {
+            combined
+                .column_by_name(column)
+                .with_context(|| format!("batch has no column named '{column}'"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut rows_by_key: BTreeMap<Vec<String>, Vec<u32>> = BTreeMap::new();
+    for row in 0..combined.num_rows() {
+        let key = partition_columns
+            .iter()
+            .map(|c| partition_value_as_string(c.as_ref(), row))
+            .collect::<Result<Vec<_>>>()?;
+        rows_by_key.entry(key).or_default().push(row as u32);
+    }
+
+    rows_by_key
+        .into_iter()
+        .map(|(key, rows)| {
+            let indices = UInt32Array::from(rows);
+            let columns = combined
+                .columns()
+                .iter()
+                .map(|c| Ok(arrow_select::take::take(c, &indices, None)?))
+                .collect::<Result<Vec<_>>>()?;
+            Ok((key, RecordBatch::try_new(schema.clone(), columns)?))
+        })
+        .collect()
+}
@@ -0,0 +1,95 @@
+//! Re-encode an existing Parquet file with a different compression, row
+//! group size, or dictionary setting, without regenerating it from source
+//! data. Lets benchmark matrices be built cheaply from one canonical
+//! generation instead of re-running the generator per setting.
+
+use anyhow::Result;
+use log::info;
+use parquet::{
+    arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter},
+    basic::Compression as ParquetCompression,
+    file::properties::WriterProperties,
+};
+
+use crate::output_target::OutputTarget;
+use crate::plan::DEFAULT_PARQUET_ROW_GROUP_BYTES;
+
+/// Options controlling how an existing Parquet file is re-encoded.
+pub struct RewriteArgs {
+    pub input_url: String,
+    pub output_url: String,
+    pub parquet_compression: ParquetCompression,
+    pub parquet_row_group_bytes: i64,
+    pub dictionary_enabled: bool,
+}
+
+/// Pick a row group size (in rows) so that, given the source file's
+/// measured bytes-per-row, each row group targets `target_bytes`. Clamped
+/// the same way as the generator's row group sizing, to keep row group
+/// counts reasonable at either extreme.
+fn rows_per_group_for_target(total_bytes: usize, total_rows: i64, target_bytes: i64) -> usize {
+    let effective_target = if target_bytes <= 0 {
+        DEFAULT_PARQUET_ROW_GROUP_BYTES
+    } else {
+        target_bytes
+    };
+    let bytes_per_row = total_bytes as f64 / total_rows.max(1) as f64;
+    let est = (effective_target as f64 / bytes_per_row).floor();
+    est.clamp(1000.0, 32767.0) as usize
+}
+
+/// Read the Parquet file at `args.input_url` and write it back out at
+/// `args.output_url` with a fresh `WriterProperties`, preserving its schema
+/// and its GeoParquet `"geo"` file metadata, if present. Other file-level
+/// key-value metadata entries are not carried over.
+pub async fn rewrite(args: RewriteArgs) -> Result<()> {
+    let (input_target, input_path) = OutputTarget::resolve_object(&args.input_url)?;
+    let (output_target, output_path) = OutputTarget::resolve_object(&args.output_url)?;
+
+    info!("Rewriting {} -> {}", input_path, output_path);
+
+    let input_bytes = input_target.store.get(&input_path).await?.bytes().await?;
+    let total_bytes = input_bytes.len();
+
+    let reader_builder = ParquetRecordBatchReaderBuilder::try_new(input_bytes)?;
+    let schema = reader_builder.schema().clone();
+    let total_rows = reader_builder.metadata().file_metadata().num_rows();
+    let geo_metadata = reader_builder
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|kvs| kvs.iter().find(|kv| kv.key == "geo").cloned());
+
+    let rows_per_group =
+        rows_per_group_for_target(total_bytes, total_rows, args.parquet_row_group_bytes);
+
+    let mut props_builder = WriterProperties::builder()
+        .set_compression(args.parquet_compression)
+        .set_max_row_group_size(rows_per_group)
+        .set_dictionary_enabled(args.dictionary_enabled);
+    if let Some(geo) = geo_metadata {
+        props_builder = props_builder.set_key_value_metadata(Some(vec![geo]));
+    }
+    let props = props_builder.build();
+
+    let reader = reader_builder.build()?;
+
+    let mut buf = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buf, schema, Some(props))?;
+    for batch in reader {
+        writer.write(&batch?)?;
+    }
+    writer.close()?;
+
+    output_target.store.put(&output_path, buf.into()).await?;
+
+    info!(
+        "Rewrote {} rows into {} ({} row group(s) of up to {} rows)",
+        total_rows,
+        output_path,
+        total_rows.div_ceil(rows_per_group as i64),
+        rows_per_group
+    );
+
+    Ok(())
+}
@@ -1,36 +1,150 @@
 use anyhow::{anyhow, Result};
-use parquet::basic::Compression as ParquetCompression;
-use std::path::PathBuf;
+use url::Url;
+
+use crate::parquet_options::ParquetWriteOptions;
+
+/// Object store backend to read the upstream Overture `division_area`
+/// dataset from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum SourceStore {
+    /// Anonymous, unsigned requests against the public Overture Maps S3
+    /// bucket (the default; ignores any AWS credentials in the environment).
+    #[default]
+    AnonymousS3,
+    /// Authenticated S3, or an S3-compatible mirror via a custom endpoint.
+    /// Credentials come from the environment, the same way the output-side
+    /// `object_store` builders already do.
+    S3 {
+        bucket: String,
+        prefix: String,
+        endpoint: Option<String>,
+    },
+    /// Google Cloud Storage, credentials from the environment.
+    Gcs { bucket: String, prefix: String },
+    /// Azure Blob Storage, credentials from the environment.
+    Azure { container: String, prefix: String },
+    /// A local filesystem copy of the Overture divisions dataset (e.g. for
+    /// offline generation), rooted at `path`.
+    Local { path: String },
+}
+
+impl SourceStore {
+    /// Parse a `--zone-source-url` target (`s3://bucket/prefix`,
+    /// `gs://bucket/prefix`, `az://container/prefix`, `file:///path`, or a
+    /// plain local path) into a non-default `SourceStore`. `s3_endpoint`
+    /// overrides the S3 endpoint for S3-compatible mirrors.
+    pub fn parse(target: &str, s3_endpoint: Option<String>) -> Result<Self> {
+        match Url::parse(target) {
+            Ok(url) if url.scheme().len() > 1 => match url.scheme() {
+                "s3" => Ok(Self::S3 {
+                    bucket: url
+                        .host_str()
+                        .ok_or_else(|| anyhow!("S3 source '{url}' is missing a bucket name"))?
+                        .to_string(),
+                    prefix: url.path().trim_start_matches('/').to_string(),
+                    endpoint: s3_endpoint,
+                }),
+                "gs" | "gcs" => Ok(Self::Gcs {
+                    bucket: url
+                        .host_str()
+                        .ok_or_else(|| anyhow!("GCS source '{url}' is missing a bucket name"))?
+                        .to_string(),
+                    prefix: url.path().trim_start_matches('/').to_string(),
+                }),
+                "az" | "azure" | "abfs" => Ok(Self::Azure {
+                    container: url
+                        .host_str()
+                        .ok_or_else(|| anyhow!("Azure source '{url}' is missing a container name"))?
+                        .to_string(),
+                    prefix: url.path().trim_start_matches('/').to_string(),
+                }),
+                "file" => Ok(Self::Local {
+                    path: url.path().to_string(),
+                }),
+                other => Err(anyhow!(
+                    "Unsupported zone source scheme '{other}://' (expected s3, gs, az, or file)"
+                )),
+            },
+            _ => Ok(Self::Local {
+                path: target.to_string(),
+            }),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct ZoneDfArgs {
     pub scale_factor: f64,
-    pub output_dir: PathBuf,
+    /// Object store backend to read the upstream Overture dataset from.
+    /// Defaults to anonymous access to the public Overture bucket.
+    pub source_store: SourceStore,
+    /// URL-style output target, e.g. `s3://bucket/prefix`, `gs://bucket/prefix`,
+    /// `az://container/prefix`, `file:///tmp/out`, or a plain local path.
+    pub output_url: String,
     pub parts: Option<i32>,
     pub part: Option<i32>,
     pub output_file_size_mb: Option<f32>,
     pub parquet_row_group_bytes: i64,
-    pub parquet_compression: ParquetCompression,
+    /// Full Parquet `WriterProperties` surface (compression, dictionary,
+    /// page sizes, writer version, per-column bloom filters, ...), applied
+    /// uniformly to every zone part file.
+    pub parquet_write_options: ParquetWriteOptions,
+    /// In-flight row buffer held by the streaming Arrow writer before a row
+    /// group is flushed to the output, in bytes. Bounds peak memory use
+    /// independent of scale factor.
+    pub write_buffer_size: usize,
+    /// Write GeoParquet `"geo"` file metadata and a per-row `bbox` covering
+    /// column instead of plain Parquet.
+    pub geo_parquet: bool,
+    /// Sort rows by the Hilbert distance of their geometry centroid before
+    /// splitting into parts, so each part file covers a compact region and
+    /// its bbox stats let readers prune whole files.
+    pub spatial_partition: bool,
+    /// Sort rows by spatial locality before writing and add `z_xmin`,
+    /// `z_ymin`, `z_xmax`, `z_ymax` envelope columns, so Parquet's own
+    /// row-group min/max statistics on coordinates become effective for
+    /// pruning even without GeoParquet support. Only applies to generation
+    /// paths that already collect the full batch set in memory before
+    /// writing (single-part, or `--spatial-partition`); see `validate`.
+    pub spatial_sort: bool,
+    /// Write a Hive-style partitioned dataset directory (one flat file per
+    /// distinct combination of these columns' values, under nested
+    /// `col=value/...` directories) instead of a single `zone.parquet`.
+    /// Requires single-part mode (`--part`); see `validate`.
+    pub partition_cols: Vec<String>,
 }
 
 impl ZoneDfArgs {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         scale_factor: f64,
-        output_dir: PathBuf,
+        source_store: SourceStore,
+        output_url: String,
         parts: Option<i32>,
         part: Option<i32>,
         output_file_size_mb: Option<f32>,
         parquet_row_group_bytes: i64,
-        parquet_compression: ParquetCompression,
+        parquet_write_options: ParquetWriteOptions,
+        write_buffer_size: usize,
+        geo_parquet: bool,
+        spatial_partition: bool,
+        spatial_sort: bool,
+        partition_cols: Vec<String>,
     ) -> Self {
         Self {
             scale_factor,
-            output_dir,
+            source_store,
+            output_url,
             parts,
             part,
             output_file_size_mb,
             parquet_row_group_bytes,
-            parquet_compression,
+            parquet_write_options,
+            write_buffer_size,
+            geo_parquet,
+            spatial_partition,
+            spatial_sort,
+            partition_cols,
         }
     }
 
@@ -47,17 +161,41 @@ impl ZoneDfArgs {
             ));
         }
 
+        if self.spatial_partition && self.part.is_some() {
+            return Err(anyhow!(
+                "--spatial-partition requires generating all parts in one invocation (omit --part)"
+            ));
+        }
+
+        if self.spatial_sort && self.part.is_none() && !self.spatial_partition {
+            return Err(anyhow!(
+                "--spatial-sort requires --part (single-part mode) or --spatial-partition, since the streaming multi-part writer never materializes the full row set to sort"
+            ));
+        }
+
+        if !self.partition_cols.is_empty() && (self.part.is_none() || self.spatial_partition) {
+            return Err(anyhow!(
+                "--zone-partition-by requires --part (single-part mode) and is incompatible with --spatial-partition, since it groups the collected rows into one flat file per Hive partition rather than splitting them across --parts"
+            ));
+        }
+
         Ok(())
     }
 
-    pub fn output_filename(&self) -> PathBuf {
+    /// Object key (relative to the resolved output target) for a single
+    /// `--part`/`--parts` invocation. Matches the flat `zone.parquet` naming
+    /// used regardless of `--parts`/`--part`.
+    pub fn output_object_path(&self) -> String {
+        "zone.parquet".to_string()
+    }
+
+    /// Object key for the given 1-based `part` when writing every part of
+    /// the table in one invocation (`--parts` without `--part`).
+    pub fn output_object_path_for_part(&self, part: i32) -> String {
         if self.parts.unwrap_or(1) > 1 {
-            // Create zone subdirectory and write parts within it
-            self.output_dir
-                .join("zone")
-                .join(format!("zone.{}.parquet", self.part.unwrap_or(1)))
+            format!("zone.{part}.parquet")
         } else {
-            self.output_dir.join("zone.parquet")
+            "zone.parquet".to_string()
         }
     }
 }
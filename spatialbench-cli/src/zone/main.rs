@@ -1,52 +1,83 @@
 use log::info;
-use parquet::basic::Compression as ParquetCompression;
 use std::io;
-use std::path::PathBuf;
 
-use super::config::ZoneDfArgs;
+use super::config::{SourceStore, ZoneDfArgs};
+use crate::parquet_options::ParquetWriteOptions;
+use crate::OutputFormat;
+
+/// Default in-flight write buffer for streaming zone generation: 64MB.
+pub const DEFAULT_WRITE_BUFFER_SIZE: usize = 64 * 1024 * 1024;
 
 /// Generates zone table in the requested format
 #[allow(clippy::too_many_arguments)]
 pub async fn generate_zone(
     format: OutputFormat,
     scale_factor: f64,
-    output_dir: PathBuf,
+    source_store: SourceStore,
+    output_url: String,
     parts: Option<i32>,
     part: Option<i32>,
     max_file_size_mb: Option<f32>,
     parquet_row_group_bytes: i64,
-    parquet_compression: ParquetCompression,
+    mut parquet_write_options: ParquetWriteOptions,
+    write_buffer_size: usize,
+    spatial_partition: bool,
+    spatial_sort: bool,
+    partition_cols: Vec<String>,
 ) -> io::Result<()> {
+    // Point/id lookups against the generated zone table filter on these
+    // columns; default to a bloom filter on them unless the caller already
+    // picked --parquet-bloom-filter columns of their own.
+    if parquet_write_options.bloom_filter_columns.is_empty() {
+        parquet_write_options.bloom_filter_columns = super::DEFAULT_BLOOM_FILTER_COLUMNS
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+    }
+
     match format {
-        OutputFormat::Parquet => {
+        OutputFormat::Parquet | OutputFormat::GeoParquet => {
             let parts = parts.unwrap_or(1);
+            let geo_parquet = format == OutputFormat::GeoParquet;
 
             if let Some(part_num) = part {
                 // Single part mode - use LIMIT/OFFSET
                 info!("Generating part {} of {} for zone table", part_num, parts);
                 let args = ZoneDfArgs::new(
                     1.0f64.max(scale_factor),
-                    output_dir,
+                    source_store,
+                    output_url,
                     Option::from(parts),
                     Option::from(part_num),
                     max_file_size_mb,
                     parquet_row_group_bytes,
-                    parquet_compression,
+                    parquet_write_options,
+                    write_buffer_size,
+                    geo_parquet,
+                    spatial_partition,
+                    spatial_sort,
+                    partition_cols.clone(),
                 );
                 super::generate_zone_parquet_single(args)
                     .await
                     .map_err(io::Error::other)
             } else {
-                // Multi-part mode - collect once and partition in memory
+                // Multi-part mode - streams batches directly to each part file
                 info!("Generating all {} part(s) for zone table", parts);
                 let args = ZoneDfArgs::new(
                     1.0f64.max(scale_factor),
-                    output_dir,
+                    source_store,
+                    output_url,
                     Option::from(parts),
                     None,
                     max_file_size_mb,
                     parquet_row_group_bytes,
-                    parquet_compression,
+                    parquet_write_options,
+                    write_buffer_size,
+                    geo_parquet,
+                    spatial_partition,
+                    spatial_sort,
+                    partition_cols.clone(),
                 );
                 super::generate_zone_parquet_multi(args)
                     .await
@@ -55,14 +86,7 @@ pub async fn generate_zone(
         }
         _ => Err(io::Error::new(
             io::ErrorKind::InvalidInput,
-            "Zone table is only supported in --format=parquet.",
+            "Zone table is only supported in --format=parquet or --format=geoparquet.",
         )),
     }
 }
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum OutputFormat {
-    Tbl,
-    Csv,
-    Parquet,
-}
@@ -0,0 +1,965 @@
+//! Generation of the `zone` table via DataFusion over the Overture Maps
+//! `division_area` dataset.
+//!
+//! This module is split from the rest of the table generators because zone
+//! data is sourced from a remote Overture parquet dataset and transformed
+//! with SQL rather than produced by the crate's row generators.
+
+pub mod config;
+pub mod main;
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use arrow_array::{Array, BinaryArray, RecordBatch, StringArray, UInt32Array, UInt64Array};
+use arrow_schema::{DataType, Schema, SchemaRef};
+use datafusion::{
+    common::config::ConfigOptions, execution::runtime_env::RuntimeEnvBuilder, prelude::*,
+    sql::TableReference,
+};
+use datafusion::execution::runtime_env::RuntimeEnv;
+use datafusion::logical_expr::{create_udf, ColumnarValue, Volatility};
+use futures::StreamExt;
+use log::{debug, info};
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::buffered::BufWriter as ObjectStoreBufWriter;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::local::LocalFileSystem;
+use object_store::ObjectStore;
+use parquet::{
+    arrow::{async_writer::AsyncArrowWriter, ArrowWriter},
+    file::metadata::KeyValue,
+    file::properties::WriterProperties,
+};
+use url::Url;
+
+use crate::geoparquet;
+use crate::hilbert;
+use crate::output_target::OutputTarget;
+use crate::parquet_options::ParquetWriteOptions;
+use crate::plan::DEFAULT_PARQUET_ROW_GROUP_BYTES;
+use config::{SourceStore, ZoneDfArgs};
+
+const OVERTURE_RELEASE_DATE: &str = "2025-08-20.1";
+const OVERTURE_S3_BUCKET: &str = "overturemaps-us-west-2";
+const OVERTURE_S3_PREFIX: &str = "release";
+
+/// The zone table's geometry column, and GeoParquet's "geo" primary column.
+const GEOMETRY_COLUMN: &str = "z_boundary";
+/// Zone boundaries from Overture's `division_area` are polygonal.
+const GEOMETRY_TYPES: &[&str] = &["Polygon", "MultiPolygon"];
+/// WGS84 world extent, used as the file-level `"geo"` bbox. The per-row
+/// `bbox` column (not this value) is what readers actually prune on, so a
+/// loose dataset-level bound here is informational rather than a pruning aid.
+const WORLD_BBOX: [f64; 4] = [-180.0, -90.0, 180.0, 90.0];
+
+/// Build the `Arc<dyn ObjectStore>` for `source`, and the base URL (scheme +
+/// bucket/container, no path) that it should be registered under with the
+/// DataFusion runtime.
+fn resolve_source_store(source: &SourceStore) -> Result<(Url, Arc<dyn ObjectStore>)> {
+    match source {
+        SourceStore::AnonymousS3 => {
+            let store = AmazonS3Builder::new()
+                .with_bucket_name(OVERTURE_S3_BUCKET)
+                .with_skip_signature(true)
+                .with_region("us-west-2")
+                .build()?;
+            let url = Url::parse(&format!("s3://{OVERTURE_S3_BUCKET}"))?;
+            Ok((url, Arc::new(store)))
+        }
+        SourceStore::S3 {
+            bucket, endpoint, ..
+        } => {
+            let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+            if let Some(endpoint) = endpoint {
+                builder = builder.with_endpoint(endpoint);
+            }
+            let url = Url::parse(&format!("s3://{bucket}"))?;
+            Ok((url, Arc::new(builder.build()?)))
+        }
+        SourceStore::Gcs { bucket, .. } => {
+            let store = GoogleCloudStorageBuilder::from_env()
+                .with_bucket_name(bucket)
+                .build()?;
+            let url = Url::parse(&format!("gs://{bucket}"))?;
+            Ok((url, Arc::new(store)))
+        }
+        SourceStore::Azure { container, .. } => {
+            let store = MicrosoftAzureBuilder::from_env()
+                .with_container_name(container)
+                .build()?;
+            let url = Url::parse(&format!("az://{container}"))?;
+            Ok((url, Arc::new(store)))
+        }
+        SourceStore::Local { path } => {
+            let store = LocalFileSystem::new_with_prefix(path)
+                .with_context(|| format!("failed to open local zone source directory '{path}'"))?;
+            Ok((Url::parse("file:///")?, Arc::new(store)))
+        }
+    }
+}
+
+/// Full URL (registered scheme + bucket/container + prefix) of the Overture
+/// `division_area` dataset to read, for `source`. For a local source the
+/// path is already the root of its registered store, so the URL is just
+/// that store's root.
+fn zones_parquet_url(source: &SourceStore) -> String {
+    match source {
+        SourceStore::AnonymousS3 => format!(
+            "s3://{}/{}/{}/theme=divisions/type=division_area/",
+            OVERTURE_S3_BUCKET, OVERTURE_S3_PREFIX, OVERTURE_RELEASE_DATE
+        ),
+        SourceStore::S3 { bucket, prefix, .. } => format!("s3://{bucket}/{prefix}"),
+        SourceStore::Gcs { bucket, prefix } => format!("gs://{bucket}/{prefix}"),
+        SourceStore::Azure { container, prefix } => format!("az://{container}/{prefix}"),
+        SourceStore::Local { .. } => "file:///".to_string(),
+    }
+}
+
+fn subtypes_for_scale_factor(sf: f64) -> Vec<&'static str> {
+    let mut v = vec!["microhood", "macrohood", "county"];
+    if sf >= 10.0 {
+        v.push("neighborhood");
+    }
+    if sf >= 100.0 {
+        v.extend_from_slice(&["localadmin", "locality", "region", "dependency"]);
+    }
+    if sf >= 1000.0 {
+        v.push("country");
+    }
+    v
+}
+
+/// FNV-1a 64-bit hash. Used (rather than `std::collections::hash_map`'s
+/// `DefaultHasher`, which isn't guaranteed stable across Rust versions) to
+/// assign each Overture feature `id` to a `--parts`/`--part` partition the
+/// same way on every run, regardless of how many rows actually exist.
+fn fnv1a_hash(id: &str) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in id.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Register the `zone_partition_hash(id)` scalar UDF used to split rows into
+/// `--parts` without relying on a row-count estimate: every id hashes to the
+/// same value on every invocation, so `zone_partition_hash(id) % parts`
+/// assigns each row to exactly one part, covering every row exactly once
+/// regardless of the table's real cardinality.
+fn register_partition_hash_udf(ctx: &SessionContext) {
+    let hash_fn = move |args: &[ColumnarValue]| -> datafusion::error::Result<ColumnarValue> {
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let ids = arrays[0]
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| {
+                datafusion::error::DataFusionError::Internal(
+                    "zone_partition_hash expects a Utf8 argument".to_string(),
+                )
+            })?;
+        let hashes: UInt64Array = ids
+            .iter()
+            .map(|v| v.map(fnv1a_hash).unwrap_or_default())
+            .collect();
+        Ok(ColumnarValue::Array(Arc::new(hashes)))
+    };
+
+    let udf = create_udf(
+        "zone_partition_hash",
+        vec![DataType::Utf8],
+        DataType::UInt64,
+        Volatility::Immutable,
+        Arc::new(hash_fn),
+    );
+    ctx.register_udf(udf);
+}
+
+fn estimated_total_rows_for_sf(sf: f64) -> i64 {
+    let mut total = 0i64;
+    for s in subtypes_for_scale_factor(sf) {
+        total += match s {
+            "microhood" => 74797,
+            "macrohood" => 42619,
+            "neighborhood" => 298615,
+            "county" => 38679,
+            "localadmin" => 19007,
+            "locality" => 555834,
+            "region" => 3905,
+            "dependency" => 53,
+            "country" => 219,
+            _ => 0,
+        };
+    }
+    if sf < 1.0 {
+        (total as f64 * sf).ceil() as i64
+    } else {
+        total
+    }
+}
+
+fn get_zone_table_stats(sf: f64) -> (f64, i64) {
+    // Returns (size_in_gb, total_rows) for the given scale factor
+    if sf < 1.0 {
+        (0.92 * sf, (156_095.0 * sf).ceil() as i64)
+    } else if sf < 10.0 {
+        (1.42, 156_095)
+    } else if sf < 100.0 {
+        (2.09, 454_710)
+    } else if sf < 1000.0 {
+        (5.68, 1_033_456)
+    } else {
+        (6.13, 1_033_675)
+    }
+}
+
+fn compute_rows_per_group_from_stats(size_gb: f64, total_rows: i64, target_bytes: i64) -> usize {
+    let total_bytes = size_gb * 1024.0 * 1024.0 * 1024.0; // Convert GB to bytes
+    let bytes_per_row = total_bytes / total_rows as f64;
+
+    // Use default if target_bytes is not specified or invalid
+    let effective_target = if target_bytes <= 0 {
+        DEFAULT_PARQUET_ROW_GROUP_BYTES
+    } else {
+        target_bytes
+    };
+
+    debug!(
+        "Using hardcoded stats: {:.2} GB, {} rows, {:.2} bytes/row, target: {} bytes",
+        size_gb, total_rows, bytes_per_row, effective_target
+    );
+
+    let est = (effective_target as f64 / bytes_per_row).floor();
+    // Keep RG count <= 32k, but avoid too-tiny RGs
+    est.clamp(1000.0, 32767.0) as usize
+}
+
+/// Default bloom filter columns for the zone table: `z_gersid` (the source
+/// Overture id) and `z_zonekey` (the generated surrogate key) are the
+/// columns downstream point/id lookups filter on.
+pub(super) const DEFAULT_BLOOM_FILTER_COLUMNS: &[&str] = &["z_gersid", "z_zonekey"];
+
+fn writer_props_with_rowgroup(
+    options: &ParquetWriteOptions,
+    rows_per_group: usize,
+    geo_parquet: bool,
+) -> WriterProperties {
+    if geo_parquet {
+        let geo_json = geoparquet::geo_metadata_json(GEOMETRY_COLUMN, GEOMETRY_TYPES, WORLD_BBOX);
+        options.build_writer_properties_with_metadata(
+            rows_per_group,
+            vec![KeyValue::new("geo".to_string(), geo_json)],
+        )
+    } else {
+        options.build_writer_properties(rows_per_group)
+    }
+}
+
+/// Apply the output-format-specific column additions to a batch before it's
+/// written: `z_xmin`/`z_ymin`/`z_xmax`/`z_ymax` flat envelope columns for
+/// `--spatial-sort`, and/or a nested GeoParquet `bbox` covering column for
+/// `--format=geoparquet`. Order matters only in that both are additive, so
+/// either or both can be enabled at once.
+fn prepare_output_batch(batch: &RecordBatch, geo_parquet: bool, spatial_sort: bool) -> Result<RecordBatch> {
+    let batch = if spatial_sort {
+        geoparquet::append_envelope_columns(batch, GEOMETRY_COLUMN, "z_")?
+    } else {
+        batch.clone()
+    };
+    if geo_parquet {
+        geoparquet::append_bbox_column(&batch, GEOMETRY_COLUMN)
+    } else {
+        Ok(batch)
+    }
+}
+
+/// Encode `batches` as parquet bytes and `put` them at `object_path` in `store`.
+#[allow(clippy::too_many_arguments)]
+async fn write_batches_to_object_store(
+    store: &Arc<dyn ObjectStore>,
+    object_path: &object_store::path::Path,
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    target_rowgroup_bytes: i64,
+    options: &ParquetWriteOptions,
+    scale_factor: f64,
+    parts: i32,
+    geo_parquet: bool,
+    spatial_sort: bool,
+) -> Result<()> {
+    let (mut size_gb, mut total_rows) = get_zone_table_stats(scale_factor);
+
+    // Use linear scaling stats for SF <= 1.0 with parts > 1
+    if scale_factor <= 1.0 && parts > 1 {
+        (size_gb, total_rows) = get_zone_table_stats(scale_factor / parts as f64);
+    }
+
+    let rows_per_group =
+        compute_rows_per_group_from_stats(size_gb, total_rows, target_rowgroup_bytes);
+    let props = writer_props_with_rowgroup(options, rows_per_group, geo_parquet);
+
+    debug!(
+        "Using row group size: {} rows (based on hardcoded stats)",
+        rows_per_group
+    );
+
+    // Rows are reordered by spatial locality up front so that the row groups
+    // a plain ArrowWriter lays out end up contiguous in space.
+    let batches = if spatial_sort {
+        vec![spatially_sort_batches(&batches, &schema)?]
+    } else {
+        batches
+    };
+
+    let mut buf = Vec::new();
+    let empty = prepare_output_batch(
+        &RecordBatch::new_empty(schema.clone()),
+        geo_parquet,
+        spatial_sort,
+    )?;
+    let mut writer = ArrowWriter::try_new(&mut buf, empty.schema(), Some(props))?;
+    for batch in batches {
+        let batch = prepare_output_batch(&batch, geo_parquet, spatial_sort)?;
+        writer.write(&batch)?;
+    }
+    writer.close()?;
+
+    store.put(object_path, buf.into()).await?;
+    Ok(())
+}
+
+/// Group `batches` by the distinct combination of `partition_cols`' values
+/// and write one flat Parquet file per combination, under nested
+/// `col=value/.../part-0.parquet` directories beneath `target`'s base path.
+#[allow(clippy::too_many_arguments)]
+async fn write_partitioned_batches_to_object_store(
+    target: &OutputTarget,
+    partition_cols: &[String],
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    target_rowgroup_bytes: i64,
+    options: &ParquetWriteOptions,
+    scale_factor: f64,
+    parts: i32,
+    geo_parquet: bool,
+    spatial_sort: bool,
+) -> Result<()> {
+    let partitions = crate::partition::partition_batches_multi(&batches, &schema, partition_cols)?;
+    info!(
+        "Writing {} zone partition(s) over columns {:?}",
+        partitions.len(),
+        partition_cols
+    );
+
+    for (key, batch) in partitions {
+        let mut object_path = target.base_path.clone();
+        for (column, value) in partition_cols.iter().zip(key.iter()) {
+            object_path = object_path.child(format!("{column}={value}"));
+        }
+        object_path = object_path.child("part-0.parquet");
+
+        write_batches_to_object_store(
+            &target.store,
+            &object_path,
+            schema.clone(),
+            vec![batch],
+            target_rowgroup_bytes,
+            options,
+            scale_factor,
+            parts,
+            geo_parquet,
+            spatial_sort,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Build the DataFusion context registered with the upstream Overture Maps
+/// dataset's object store, and return the filtered dataframe for
+/// `scale_factor`, restricted to its subtypes by an in-query `subtype`
+/// predicate.
+async fn zone_filtered_dataframe(
+    scale_factor: f64,
+    source: &SourceStore,
+) -> Result<(SessionContext, DataFrame)> {
+    let subtypes = subtypes_for_scale_factor(scale_factor);
+    info!("Selected subtypes for SF {}: {:?}", scale_factor, subtypes);
+
+    let mut cfg = ConfigOptions::new();
+    cfg.execution.target_partitions = 1;
+
+    let rt: Arc<RuntimeEnv> = Arc::new(RuntimeEnvBuilder::new().build()?);
+
+    let (store_url, store) = resolve_source_store(source)?;
+    rt.register_object_store(&store_url, store);
+
+    let ctx = SessionContext::new_with_config_rt(SessionConfig::from(cfg), rt);
+    register_partition_hash_udf(&ctx);
+
+    let url = zones_parquet_url(source);
+    info!("Reading parquet data from: {}", url);
+
+    // The Overture `division_area` prefix is a flat collection of Parquet
+    // files (not Hive-partitioned by `subtype`/`country`), so read it as an
+    // ordinary dataset and filter `subtype`/`country` as in-file row
+    // columns below, rather than via partition pruning.
+    let mut df = ctx
+        .read_parquet(url.as_str(), ParquetReadOptions::default())
+        .await
+        .with_context(|| format!("failed to read zone parquet data from {url}"))?;
+
+    let mut pred = col("subtype").eq(lit("__never__"));
+    for s in subtypes {
+        pred = pred.or(col("subtype").eq(lit(s)));
+    }
+    df = df.filter(pred.and(col("is_land").eq(lit(true))))?;
+
+    Ok((ctx, df))
+}
+
+/// Project the raw Overture rows into the `zone` table schema, restricted to
+/// the `part`-th of `parts` partitions (1-based) via a `zone_partition_hash`
+/// predicate rather than `LIMIT`/`OFFSET`, so the selection is exact and
+/// reproducible regardless of the table's real row count.
+///
+/// `z_zonekey` is assigned from `ROW_NUMBER() OVER (ORDER BY id)` *within*
+/// this partition, striped by `parts` so that values from different parts of
+/// the same table can never collide: part `p`'s keys are `p`, `p + parts`,
+/// `p + 2*parts`, ... This keeps keys globally unique and monotonic in `id`
+/// order within a part without requiring a precomputed per-part offset.
+async fn project_zone_columns(
+    ctx: &SessionContext,
+    df: DataFrame,
+    parts: i64,
+    part: i64,
+) -> Result<DataFrame> {
+    ctx.register_table(TableReference::bare("zone_filtered"), df.into_view())?;
+
+    let sql = format!(
+        r#"
+        SELECT
+          CAST((ROW_NUMBER() OVER (ORDER BY id) - 1) * {parts} + {part} AS BIGINT) AS z_zonekey,
+          COALESCE(id, '')            AS z_gersid,
+          COALESCE(country, '')       AS z_country,
+          COALESCE(region,  '')       AS z_region,
+          COALESCE(names.primary, '') AS z_name,
+          COALESCE(subtype, '')       AS z_subtype,
+          geometry                    AS z_boundary
+        FROM zone_filtered
+        WHERE zone_partition_hash(id) % {parts} = {part} - 1
+        "#
+    );
+    Ok(ctx.sql(&sql).await?)
+}
+
+fn arrow_schema_of(df: &DataFrame) -> SchemaRef {
+    Arc::new(Schema::new(
+        df.schema()
+            .fields()
+            .iter()
+            .map(|f| f.as_ref().clone())
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Generate a single `--part`/`--parts` slice of the zone table, selecting
+/// its rows via a `zone_partition_hash(id) % parts` predicate rather than
+/// `LIMIT`/`OFFSET` over a row-count estimate. This makes each part exactly
+/// reproducible and the set of parts collectively exhaustive regardless of
+/// how the real row count compares to any estimate.
+pub async fn generate_zone_parquet_single(args: ZoneDfArgs) -> Result<()> {
+    args.validate()?;
+
+    let parts = args.parts.unwrap_or(1) as i64;
+    let part = args.part.unwrap_or(1) as i64;
+
+    info!(
+        "Starting zone parquet generation with scale factor {}",
+        args.scale_factor
+    );
+
+    let (ctx, df) = zone_filtered_dataframe(args.scale_factor, &args.source_store).await?;
+
+    info!("Selecting part {} of {} via partition hash", part, parts);
+
+    let df2 = project_zone_columns(&ctx, df, parts, part).await?;
+
+    let t0 = Instant::now();
+    let batches = df2.clone().collect().await?;
+    let collect_dur = t0.elapsed();
+
+    let total_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+    info!(
+        "Collected {} record batches with {} total rows in {:?}",
+        batches.len(),
+        total_rows,
+        collect_dur
+    );
+
+    let schema = arrow_schema_of(&df2);
+
+    let target = OutputTarget::resolve(&args.output_url)?;
+
+    let t1 = Instant::now();
+    let destination = if args.partition_cols.is_empty() {
+        let object_path = target.base_path.child(args.output_object_path());
+        write_batches_to_object_store(
+            &target.store,
+            &object_path,
+            schema,
+            batches,
+            args.parquet_row_group_bytes,
+            &args.parquet_write_options,
+            args.scale_factor,
+            args.parts.unwrap_or(1),
+            args.geo_parquet,
+            args.spatial_sort,
+        )
+        .await?;
+        object_path.to_string()
+    } else {
+        write_partitioned_batches_to_object_store(
+            &target,
+            &args.partition_cols,
+            schema,
+            batches,
+            args.parquet_row_group_bytes,
+            &args.parquet_write_options,
+            args.scale_factor,
+            args.parts.unwrap_or(1),
+            args.geo_parquet,
+            args.spatial_sort,
+        )
+        .await?;
+        format!("{} (partitioned by {:?})", target.base_path, args.partition_cols)
+    };
+    let write_dur = t1.elapsed();
+
+    info!(
+        "Zone -> {} (part {}/{}). collect={:?}, write={:?}, total_rows={}",
+        destination, part, parts, collect_dur, write_dur, total_rows
+    );
+
+    Ok(())
+}
+
+/// Row counts for each 1-based part, splitting `total_rows` as evenly as
+/// possible (first `total_rows % parts` parts get one extra row).
+fn part_row_budgets(total_rows: i64, parts: i64) -> Vec<i64> {
+    let base = total_rows / parts;
+    let rem = total_rows % parts;
+    (0..parts)
+        .map(|i| base + if i < rem { 1 } else { 0 })
+        .collect()
+}
+
+async fn open_part_writer(
+    target: &OutputTarget,
+    object_path: &object_store::path::Path,
+    schema: SchemaRef,
+    props: WriterProperties,
+    write_buffer_size: usize,
+) -> Result<AsyncArrowWriter<ObjectStoreBufWriter>> {
+    let sink = ObjectStoreBufWriter::new(Arc::clone(&target.store), object_path.clone());
+    Ok(AsyncArrowWriter::try_new(
+        sink,
+        schema,
+        write_buffer_size,
+        Some(props),
+    )?)
+}
+
+/// Schema written to each part file: the base zone schema, plus a trailing
+/// `bbox` struct column when `geo_parquet` is enabled.
+fn output_schema(schema: &SchemaRef, geo_parquet: bool) -> Result<SchemaRef> {
+    if !geo_parquet {
+        return Ok(schema.clone());
+    }
+    let with_bbox = geoparquet::append_bbox_column(
+        &RecordBatch::new_empty(schema.clone()),
+        GEOMETRY_COLUMN,
+    )?;
+    Ok(with_bbox.schema())
+}
+
+/// Concatenate `batches` and reorder their rows by the Hilbert distance of
+/// each row's `z_boundary` centroid, so that contiguous row ranges cover
+/// compact regions of the map.
+fn spatially_sort_batches(batches: &[RecordBatch], schema: &SchemaRef) -> Result<RecordBatch> {
+    let combined = arrow_select::concat::concat_batches(schema, batches)?;
+
+    let geometry = combined
+        .column_by_name(GEOMETRY_COLUMN)
+        .with_context(|| format!("batch has no column named '{GEOMETRY_COLUMN}'"))?
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .with_context(|| format!("column '{GEOMETRY_COLUMN}' is not binary-encoded WKB"))?;
+
+    let mut distances = Vec::with_capacity(combined.num_rows());
+    for i in 0..combined.num_rows() {
+        distances.push(if geometry.is_null(i) {
+            u64::MAX
+        } else {
+            hilbert::hilbert_distance_for_wkb(geometry.value(i))?
+        });
+    }
+
+    let mut order: Vec<u32> = (0..combined.num_rows() as u32).collect();
+    order.sort_by_key(|&i| distances[i as usize]);
+    let indices = UInt32Array::from(order);
+
+    let columns = combined
+        .columns()
+        .iter()
+        .map(|c| Ok(arrow_select::take::take(c, &indices, None)?))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// Generate every part of the zone table in one invocation, sorting rows
+/// by Hilbert-curve distance before splitting into parts so each part file
+/// covers a compact region of the map and its row-group bbox stats let
+/// readers prune whole files instead of just row groups within a file.
+///
+/// Unlike `generate_zone_parquet_multi`'s streaming writer, this requires
+/// collecting the filtered dataset into memory up front, since the sort
+/// order depends on every row. Peak memory therefore scales with the whole
+/// table rather than a single row group.
+async fn generate_zone_parquet_multi_spatial(args: ZoneDfArgs) -> Result<()> {
+    let parts = args.parts.unwrap_or(1) as i64;
+    info!(
+        "Starting spatially-partitioned zone parquet generation (all {} part(s)) with scale factor {}",
+        parts, args.scale_factor
+    );
+
+    let (ctx, df) = zone_filtered_dataframe(args.scale_factor, &args.source_store).await?;
+    let df2 = project_zone_columns(&ctx, df, 1, 1).await?;
+    let schema = arrow_schema_of(&df2);
+
+    let t0 = Instant::now();
+    let batches = df2.collect().await?;
+    let sorted = spatially_sort_batches(&batches, &schema)?;
+    let collect_dur = t0.elapsed();
+
+    let total_rows = sorted.num_rows() as i64;
+    let row_budgets = part_row_budgets(total_rows, parts);
+    info!(
+        "Collected and Hilbert-sorted {} total rows in {:?}",
+        total_rows, collect_dur
+    );
+
+    let target = OutputTarget::resolve(&args.output_url)?;
+
+    let t1 = Instant::now();
+    let mut offset = 0i64;
+    for (idx, rows_this) in row_budgets.iter().enumerate() {
+        let part = idx as i32 + 1;
+        let part_batch = sorted.slice(offset as usize, *rows_this as usize);
+        let object_path = target
+            .base_path
+            .child(args.output_object_path_for_part(part));
+
+        write_batches_to_object_store(
+            &target.store,
+            &object_path,
+            schema.clone(),
+            vec![part_batch],
+            args.parquet_row_group_bytes,
+            &args.parquet_write_options,
+            args.scale_factor,
+            args.parts.unwrap_or(1),
+            args.geo_parquet,
+            args.spatial_sort,
+        )
+        .await?;
+        offset += rows_this;
+    }
+    let write_dur = t1.elapsed();
+
+    info!(
+        "Zone -> {} part(s) under {} (spatially partitioned). collect+sort={:?}, write={:?}, total_rows={}",
+        parts, target.base_path, collect_dur, write_dur, total_rows
+    );
+
+    Ok(())
+}
+
+/// Generate every part of the zone table in one invocation, streaming
+/// batches straight from the DataFusion query into a rolling set of part
+/// files so peak memory stays bounded regardless of scale factor.
+pub async fn generate_zone_parquet_multi(args: ZoneDfArgs) -> Result<()> {
+    args.validate()?;
+
+    if args.spatial_partition {
+        return generate_zone_parquet_multi_spatial(args).await;
+    }
+
+    let parts = args.parts.unwrap_or(1) as i64;
+    info!(
+        "Starting streaming zone parquet generation (all {} part(s)) with scale factor {}",
+        parts, args.scale_factor
+    );
+
+    let (ctx, df) = zone_filtered_dataframe(args.scale_factor, &args.source_store).await?;
+    let df2 = project_zone_columns(&ctx, df, 1, 1).await?;
+    let schema = arrow_schema_of(&df2);
+
+    // Part boundaries are derived from hardcoded row estimates
+    // (`estimated_total_rows_for_sf`) rather than an upfront collect, so this
+    // path never has to materialize the whole table in memory.
+    let total_rows_est = estimated_total_rows_for_sf(args.scale_factor);
+    let row_budgets = part_row_budgets(total_rows_est, parts);
+
+    let (mut size_gb, mut stat_rows) = get_zone_table_stats(args.scale_factor);
+    if args.scale_factor <= 1.0 && parts > 1 {
+        (size_gb, stat_rows) = get_zone_table_stats(args.scale_factor / parts as f64);
+    }
+    let rows_per_group =
+        compute_rows_per_group_from_stats(size_gb, stat_rows, args.parquet_row_group_bytes);
+    let props =
+        writer_props_with_rowgroup(&args.parquet_write_options, rows_per_group, args.geo_parquet);
+    let part_schema = output_schema(&schema, args.geo_parquet)?;
+
+    let target = OutputTarget::resolve(&args.output_url)?;
+
+    let mut current_part = 1i64;
+    let mut current_part_rows_written = 0i64;
+    let mut object_path = target
+        .base_path
+        .child(args.output_object_path_for_part(current_part as i32));
+    let mut writer = open_part_writer(
+        &target,
+        &object_path,
+        part_schema.clone(),
+        props.clone(),
+        args.write_buffer_size,
+    )
+    .await?;
+
+    let t0 = Instant::now();
+    let mut total_rows_written = 0i64;
+    let mut stream = df2.execute_stream().await?;
+    while let Some(batch) = stream.next().await {
+        let mut batch = batch?;
+        while batch.num_rows() > 0 {
+            let budget = row_budgets[(current_part - 1) as usize];
+            let remaining_in_part = (budget - current_part_rows_written).max(0) as usize;
+
+            if remaining_in_part == 0 && current_part < parts {
+                writer.close().await?;
+                current_part += 1;
+                current_part_rows_written = 0;
+                object_path = target
+                    .base_path
+                    .child(args.output_object_path_for_part(current_part as i32));
+                writer = open_part_writer(
+                    &target,
+                    &object_path,
+                    part_schema.clone(),
+                    props.clone(),
+                    args.write_buffer_size,
+                )
+                .await?;
+                continue;
+            }
+
+            // The last part absorbs any rows beyond the estimate (the
+            // query's actual row count may differ slightly from the
+            // hardcoded stats used to size the budgets).
+            let take = remaining_in_part.max(1).min(batch.num_rows());
+            let to_write = batch.slice(0, take);
+            let to_write = if args.geo_parquet {
+                geoparquet::append_bbox_column(&to_write, GEOMETRY_COLUMN)?
+            } else {
+                to_write
+            };
+            writer.write(&to_write).await?;
+            current_part_rows_written += take as i64;
+            total_rows_written += take as i64;
+            batch = batch.slice(take, batch.num_rows() - take);
+        }
+    }
+    writer.close().await?;
+    let write_dur = t0.elapsed();
+
+    info!(
+        "Zone -> {} part(s) under {}. streamed {} total rows in {:?}",
+        parts, target.base_path, total_rows_written, write_dur
+    );
+
+    Ok(())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::basic::Compression;
+
+    fn create_test_args(scale_factor: f64) -> ZoneDfArgs {
+        ZoneDfArgs::new(
+            scale_factor,
+            SourceStore::default(),
+            "/tmp/zone-test-output".to_string(),
+            Some(1),
+            Some(1),
+            None,
+            DEFAULT_PARQUET_ROW_GROUP_BYTES,
+            ParquetWriteOptions {
+                compression: Compression::SNAPPY,
+                data_page_size_bytes: None,
+                write_batch_size: None,
+                writer_version: crate::parquet_options::ParquetWriterVersion::V2,
+                max_row_group_rows: None,
+                dictionary_enabled: true,
+                dictionary_page_size_bytes: None,
+                bloom_filter_columns: Vec::new(),
+                bloom_filter_fpp: 0.01,
+            },
+            main::DEFAULT_WRITE_BUFFER_SIZE,
+            false,
+            false,
+            false,
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn test_zone_generation_invalid_part() {
+        let mut args = create_test_args(1.0);
+        args.parts = Some(2);
+        args.part = Some(3); // Invalid part number
+
+        assert!(
+            args.validate().is_err(),
+            "Should fail with invalid part number"
+        );
+    }
+
+    #[test]
+    fn test_subtypes_for_different_scale_factors() {
+        // Test scale factor categorization
+        let sf_01_subtypes = subtypes_for_scale_factor(0.1);
+        assert_eq!(sf_01_subtypes, vec!["microhood", "macrohood", "county"]);
+
+        let sf_10_subtypes = subtypes_for_scale_factor(10.0);
+        assert_eq!(
+            sf_10_subtypes,
+            vec!["microhood", "macrohood", "county", "neighborhood"]
+        );
+
+        let sf_100_subtypes = subtypes_for_scale_factor(100.0);
+        assert!(sf_100_subtypes.contains(&"localadmin"));
+        assert!(sf_100_subtypes.contains(&"locality"));
+
+        let sf_1000_subtypes = subtypes_for_scale_factor(1000.0);
+        assert!(sf_1000_subtypes.contains(&"country"));
+    }
+
+    #[test]
+    fn test_partition_distribution_logic() {
+        // `zone_partition_hash(id) % parts` must assign every id to exactly
+        // one part, and the parts together must cover every id -- this holds
+        // regardless of the real row count, unlike the old estimate-driven
+        // LIMIT/OFFSET split. Verify against a real id set rather than a
+        // hardcoded estimate.
+        let ids: Vec<String> = (0..1000).map(|i| format!("overture-id-{i}")).collect();
+        let parts = 7i64;
+
+        let mut rows_per_part = vec![0i64; parts as usize];
+        let mut seen = std::collections::HashSet::new();
+        for id in &ids {
+            let part = (fnv1a_hash(id) % parts as u64) as usize;
+            rows_per_part[part] += 1;
+            assert!(seen.insert(id.clone()), "id {id} assigned more than once");
+        }
+
+        // Completeness: every id landed in exactly one part.
+        assert_eq!(rows_per_part.iter().sum::<i64>(), ids.len() as i64);
+        // Determinism: re-hashing the same id set gives the same assignment.
+        for id in &ids {
+            let part_a = fnv1a_hash(id) % parts as u64;
+            let part_b = fnv1a_hash(id) % parts as u64;
+            assert_eq!(part_a, part_b);
+        }
+    }
+
+    #[test]
+    fn test_rows_per_group_bounds() {
+        // Test that compute_rows_per_group_from_stats respects bounds
+
+        // Test minimum bound (should be at least 1000)
+        let rows_per_group_tiny = compute_rows_per_group_from_stats(0.001, 1000, 1_000_000);
+        assert!(rows_per_group_tiny >= 1000);
+
+        // Test maximum bound (should not exceed 32767)
+        let rows_per_group_huge = compute_rows_per_group_from_stats(1000.0, 1000, 1);
+        assert!(rows_per_group_huge <= 32767);
+
+        // Test negative target bytes falls back to default
+        let rows_per_group_negative = compute_rows_per_group_from_stats(1.0, 100000, -1);
+        let rows_per_group_default =
+            compute_rows_per_group_from_stats(1.0, 100000, DEFAULT_PARQUET_ROW_GROUP_BYTES);
+        assert_eq!(rows_per_group_negative, rows_per_group_default);
+    }
+
+    #[test]
+    fn test_subtype_selection_logic() {
+        // Test the cumulative nature of subtype selection
+        let base_subtypes = subtypes_for_scale_factor(1.0);
+        let sf10_subtypes = subtypes_for_scale_factor(10.0);
+        let sf100_subtypes = subtypes_for_scale_factor(100.0);
+        let sf1000_subtypes = subtypes_for_scale_factor(1000.0);
+
+        // Each higher scale factor should include all previous subtypes
+        for subtype in &base_subtypes {
+            assert!(sf10_subtypes.contains(subtype));
+            assert!(sf100_subtypes.contains(subtype));
+            assert!(sf1000_subtypes.contains(subtype));
+        }
+
+        for subtype in &sf10_subtypes {
+            assert!(sf100_subtypes.contains(subtype));
+            assert!(sf1000_subtypes.contains(subtype));
+        }
+
+        for subtype in &sf100_subtypes {
+            assert!(sf1000_subtypes.contains(subtype));
+        }
+
+        // Verify progressive addition
+        assert!(sf10_subtypes.len() > base_subtypes.len());
+        assert!(sf100_subtypes.len() > sf10_subtypes.len());
+        assert!(sf1000_subtypes.len() > sf100_subtypes.len());
+    }
+
+    #[test]
+    fn test_estimated_rows_scaling_consistency() {
+        // Test that estimated rows scale proportionally for SF < 1.0
+        let base_rows = estimated_total_rows_for_sf(1.0);
+        let half_rows = estimated_total_rows_for_sf(0.5);
+        let quarter_rows = estimated_total_rows_for_sf(0.25);
+
+        // Should scale proportionally (within rounding)
+        assert!((half_rows as f64 - (base_rows as f64 * 0.5)).abs() < 1.0);
+        assert!((quarter_rows as f64 - (base_rows as f64 * 0.25)).abs() < 1.0);
+
+        // Test that SF >= 1.0 gives discrete jumps (not proportional scaling)
+        let sf1_rows = estimated_total_rows_for_sf(1.0);
+        let sf5_rows = estimated_total_rows_for_sf(5.0);
+        let sf10_rows = estimated_total_rows_for_sf(10.0);
+
+        // These should be equal (same category)
+        assert_eq!(sf1_rows, sf5_rows);
+
+        // This should be different (different category)
+        assert_ne!(sf5_rows, sf10_rows);
+    }
+}
@@ -251,6 +251,100 @@ fn test_spatialbench_cli_parts() {
     assert_eq!(output_contents, reference_file);
 }
 
+/// Test generating every part of the trip table in one invocation via
+/// --generate-all-parts, checking the output is bit-identical to stitching
+/// together the per-process --part runs in `test_spatialbench_cli_parts`.
+#[test]
+fn test_spatialbench_cli_generate_all_parts() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let num_parts = 4;
+
+    Command::cargo_bin("spatialbench-cli")
+        .expect("Binary not found")
+        .arg("--scale-factor")
+        .arg("0.001")
+        .arg("--format")
+        .arg("tbl")
+        .arg("--output-dir")
+        .arg(temp_dir.path())
+        .arg("--parts")
+        .arg(num_parts.to_string())
+        .arg("--generate-all-parts")
+        .arg("--tables")
+        .arg("trip")
+        .assert()
+        .success();
+
+    let mut output_contents = Vec::new();
+    for part in 1..=num_parts {
+        let generated_file = temp_dir.path().join(format!("trip.{part}.tbl"));
+        assert!(
+            generated_file.exists(),
+            "File {:?} does not exist",
+            generated_file
+        );
+        let generated_contents =
+            fs::read_to_string(generated_file).expect("Failed to read generated file");
+        output_contents.append(&mut generated_contents.into_bytes());
+    }
+    let output_contents =
+        String::from_utf8(output_contents).expect("Failed to convert output contents to string");
+
+    let reference_file = read_reference_file("trip", "v1");
+    assert_eq!(output_contents, reference_file);
+}
+
+#[test]
+fn test_spatialbench_cli_generate_all_parts_requires_parts() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+
+    Command::cargo_bin("spatialbench-cli")
+        .expect("Binary not found")
+        .arg("--output-dir")
+        .arg(temp_dir.path())
+        .arg("--generate-all-parts")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "--generate-all-parts requires --parts to be set",
+        ));
+}
+
+#[test]
+fn test_spatialbench_cli_generate_all_parts_rejects_part() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+
+    Command::cargo_bin("spatialbench-cli")
+        .expect("Binary not found")
+        .arg("--output-dir")
+        .arg(temp_dir.path())
+        .arg("--parts")
+        .arg("4")
+        .arg("--part")
+        .arg("1")
+        .arg("--generate-all-parts")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Cannot specify --part with --generate-all-parts",
+        ));
+}
+
+#[test]
+fn test_spatialbench_cli_generate_all_parts_rejects_stdout() {
+    Command::cargo_bin("spatialbench-cli")
+        .expect("Binary not found")
+        .arg("--parts")
+        .arg("4")
+        .arg("--generate-all-parts")
+        .arg("--stdout")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "--generate-all-parts is not supported with --stdout",
+        ));
+}
+
 #[tokio::test]
 async fn test_write_parquet_trips() {
     // Run the CLI command to generate parquet data
@@ -571,6 +665,147 @@ async fn test_incompatible_options_warnings() {
         ));
 }
 
+/// Test that --format=geoparquet writes the GeoParquet `"geo"` file metadata
+/// and a per-row `bbox{xmin,ymin,xmax,ymax}` covering column whose values
+/// equal each row's geometry envelope.
+#[test]
+fn test_write_geoparquet_bbox_column() {
+    use arrow_array::{Array, BinaryArray, Float64Array, StructArray};
+    use geo::BoundingRect;
+
+    let output_dir = tempdir().unwrap();
+    Command::cargo_bin("spatialbench-cli")
+        .expect("Binary not found")
+        .arg("--scale-factor")
+        .arg("0.001")
+        .arg("--tables")
+        .arg("trip")
+        .arg("--format")
+        .arg("geoparquet")
+        .arg("--output-dir")
+        .arg(output_dir.path())
+        .assert()
+        .success();
+
+    let output_path = output_dir.path().join("trip.parquet");
+    let file = File::open(&output_path).expect("Failed to open geoparquet file");
+
+    let mut metadata_reader = ParquetMetaDataReader::new();
+    metadata_reader.try_parse(&file).unwrap();
+    let metadata = metadata_reader.finish().unwrap();
+    let geo_json = metadata
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|kvs| kvs.iter().find(|kv| kv.key == "geo"))
+        .and_then(|kv| kv.value.clone())
+        .expect("Expected a 'geo' key in file metadata");
+    assert!(geo_json.contains("\"t_pickuploc\""));
+    assert!(geo_json.contains("\"encoding\":\"WKB\""));
+
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new(file).expect("Failed to build parquet reader");
+    let reader = builder.build().expect("Failed to build record batch reader");
+    let batches: Vec<RecordBatch> = reader.collect::<Result<_, _>>().unwrap();
+    assert!(!batches.is_empty());
+
+    for batch in &batches {
+        let geometry = batch
+            .column_by_name("t_pickuploc")
+            .expect("missing t_pickuploc column")
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .expect("t_pickuploc is not WKB-encoded");
+        let bbox = batch
+            .column_by_name("bbox")
+            .expect("missing bbox column")
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .expect("bbox is not a struct column");
+        let xmin = bbox.column(0).as_any().downcast_ref::<Float64Array>().unwrap();
+        let ymin = bbox.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        let xmax = bbox.column(2).as_any().downcast_ref::<Float64Array>().unwrap();
+        let ymax = bbox.column(3).as_any().downcast_ref::<Float64Array>().unwrap();
+
+        for i in 0..batch.num_rows() {
+            assert!(!geometry.is_null(i), "expected every trip pickup location to be non-null");
+            let mut cursor = std::io::Cursor::new(geometry.value(i));
+            let geom: geo_types::Geometry<f64> = wkb::reader::read_wkb(&mut cursor).unwrap();
+            let rect = geom.bounding_rect().unwrap();
+            assert_eq!(xmin.value(i), rect.min().x);
+            assert_eq!(ymin.value(i), rect.min().y);
+            assert_eq!(xmax.value(i), rect.max().x);
+            assert_eq!(ymax.value(i), rect.max().y);
+        }
+    }
+}
+
+/// Test that --partition-by writes a Hive-style `table/col=value/part-0.parquet`
+/// layout instead of a single `table.parquet` file.
+#[test]
+fn test_write_parquet_partition_by() {
+    let output_dir = tempdir().unwrap();
+    Command::cargo_bin("spatialbench-cli")
+        .expect("Binary not found")
+        .arg("--scale-factor")
+        .arg("0.001")
+        .arg("--tables")
+        .arg("trip")
+        .arg("--output-dir")
+        .arg(output_dir.path())
+        .arg("--partition-by")
+        .arg("t_pickup_zone")
+        .assert()
+        .success();
+
+    let table_dir = output_dir.path().join("trip");
+    assert!(
+        table_dir.is_dir(),
+        "Expected a trip/ partition directory, not a single trip.parquet file"
+    );
+
+    let partition_dirs: Vec<String> = fs::read_dir(&table_dir)
+        .expect("Failed to read trip/ partition directory")
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert!(
+        !partition_dirs.is_empty(),
+        "Expected at least one t_pickup_zone=<value> partition directory"
+    );
+    for partition_dir in &partition_dirs {
+        assert!(
+            partition_dir.starts_with("t_pickup_zone="),
+            "Unexpected partition directory name {partition_dir:?}"
+        );
+        let part_file = table_dir.join(partition_dir).join("part-0.parquet");
+        assert!(part_file.exists(), "Expected {:?} to exist", part_file);
+    }
+}
+
+/// --partition-by changes the on-disk layout and isn't meaningful for
+/// row-oriented tbl/csv output, so it must be rejected like the zone
+/// table's own --format restriction.
+#[test]
+fn test_partition_by_rejected_for_tbl() {
+    let output_dir = tempdir().unwrap();
+    Command::cargo_bin("spatialbench-cli")
+        .expect("Binary not found")
+        .arg("--format")
+        .arg("tbl")
+        .arg("--scale-factor")
+        .arg("0.001")
+        .arg("--tables")
+        .arg("trip")
+        .arg("--output-dir")
+        .arg(output_dir.path())
+        .arg("--partition-by")
+        .arg("t_pickup_zone")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "--partition-by is only supported with --format=parquet",
+        ));
+}
+
 #[test]
 fn test_zone_generation_tbl_fails() {
     let temp_dir = tempdir().expect("Failed to create temporary directory");